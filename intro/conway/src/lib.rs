@@ -61,7 +61,7 @@ impl<T: Clone + Default> Grid<T> {
         if col != self.cols - 1 {
             neighbours.push((row, col + 1));
         }
-        if row != self.cols - 1 && col != 0 {
+        if row != self.rows - 1 && col != 0 {
             neighbours.push((row + 1, col - 1));
         }
         if row != self.rows - 1 {
@@ -77,18 +77,13 @@ impl<T: Clone + Default> Grid<T> {
 
 ////////////////////////////////////////////////////////////////////////////////
 
-#[derive(Clone, Copy, PartialEq, Eq)]
+#[derive(Clone, Copy, Default, PartialEq, Eq)]
 pub enum Cell {
+    #[default]
     Dead,
     Alive,
 }
 
-impl Default for Cell {
-    fn default() -> Self {
-        Self::Dead
-    }
-}
-
 ////////////////////////////////////////////////////////////////////////////////
 
 #[derive(PartialEq, Eq)]
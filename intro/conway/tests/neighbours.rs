@@ -0,0 +1,36 @@
+use conway::{Cell, GameOfLife, Grid};
+
+fn sorted(mut neighbours: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    neighbours.sort();
+    neighbours
+}
+
+#[test]
+fn neighbours_on_non_square_grids() {
+    let wide = Grid::<Cell>::new(2, 3);
+    assert_eq!(
+        sorted(wide.neighbours(1, 1)),
+        [(0, 0), (0, 1), (0, 2), (1, 0), (1, 2)]
+    );
+
+    let tall = Grid::<Cell>::new(3, 2);
+    assert_eq!(sorted(tall.neighbours(0, 1)), [(0, 0), (1, 0), (1, 1)]);
+}
+
+#[test]
+fn step_on_a_wide_grid() {
+    let alive = [Cell::Alive; 6];
+    let mut game = GameOfLife::from_grid(Grid::from_slice(&alive, 2, 3));
+    game.step();
+
+    // Corners keep three live neighbours, the middle column is overcrowded.
+    let expected = [
+        Cell::Alive,
+        Cell::Dead,
+        Cell::Alive,
+        Cell::Alive,
+        Cell::Dead,
+        Cell::Alive,
+    ];
+    assert!(*game.get_grid() == Grid::from_slice(&expected, 2, 3));
+}
@@ -0,0 +1,180 @@
+use std::{cell::RefCell, collections::VecDeque, rc::Rc};
+use thiserror::Error;
+
+pub use crate::SendError;
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Unlike the point-to-point channels a subscriber can fall behind the buffer,
+// so it gets its own error type.
+#[derive(Error, Debug)]
+pub enum ReceiveError {
+    #[error("channel is empty")]
+    Empty,
+    #[error("channel is closed")]
+    Closed,
+    #[error("receiver lagged behind by {0} messages")]
+    Lagged(u64),
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Messages live in a ring buffer shared by all subscribers. Every receiver
+// keeps the sequence number of the next message it wants; once that message
+// has been overwritten the receiver reports how many it missed.
+struct Shared<T> {
+    buffer: VecDeque<T>,
+    head: u64,
+    capacity: usize,
+    is_close: bool,
+    senders: usize,
+    receivers: usize,
+}
+
+impl<T> Shared<T> {
+    fn tail(&self) -> u64 {
+        self.head + self.buffer.len() as u64
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Sender<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+}
+
+impl<T> Sender<T> {
+    // Fails once the senders are closed or nobody is subscribed.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut shared = self.shared.borrow_mut();
+        if shared.is_close || shared.receivers == 0 {
+            return Err(SendError { value });
+        }
+
+        if shared.buffer.len() == shared.capacity {
+            shared.buffer.pop_front();
+            shared.head += 1;
+        }
+        shared.buffer.push_back(value);
+        Ok(())
+    }
+
+    pub fn subscribe(&self) -> Receiver<T> {
+        let mut shared = self.shared.borrow_mut();
+        shared.receivers += 1;
+
+        Receiver {
+            next: shared.tail(),
+            shared: self.shared.clone(),
+        }
+    }
+
+    pub fn receiver_count(&self) -> usize {
+        self.shared.borrow().receivers
+    }
+
+    pub fn is_closed(&self) -> bool {
+        let shared = self.shared.borrow();
+        shared.is_close || shared.receivers == 0
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.shared, &other.shared)
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().senders += 1;
+        Self {
+            shared: self.shared.clone(),
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut shared = self.shared.borrow_mut();
+        shared.senders -= 1;
+        if shared.senders == 0 {
+            shared.is_close = true
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Receiver<T> {
+    shared: Rc<RefCell<Shared<T>>>,
+    next: u64,
+}
+
+impl<T: Clone> Receiver<T> {
+    // After `Lagged` the receiver continues with the oldest message still buffered.
+    pub fn recv(&mut self) -> Result<T, ReceiveError> {
+        let shared = self.shared.borrow();
+        if self.next < shared.head {
+            let missed = shared.head - self.next;
+            self.next = shared.head;
+            return Err(ReceiveError::Lagged(missed));
+        }
+
+        match shared.buffer.get((self.next - shared.head) as usize) {
+            Some(value) => {
+                self.next += 1;
+                Ok(value.clone())
+            }
+            None if shared.is_close => Err(ReceiveError::Closed),
+            None => Err(ReceiveError::Empty),
+        }
+    }
+}
+
+impl<T> Receiver<T> {
+    pub fn len(&self) -> usize {
+        let shared = self.shared.borrow();
+        (shared.tail() - self.next.max(shared.head)) as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for Receiver<T> {
+    fn clone(&self) -> Self {
+        self.shared.borrow_mut().receivers += 1;
+        Self {
+            shared: self.shared.clone(),
+            next: self.next,
+        }
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.shared.borrow_mut().receivers -= 1;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn channel<T>(capacity: usize) -> (Sender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+
+    let shared = Rc::new(RefCell::new(Shared {
+        buffer: VecDeque::with_capacity(capacity),
+        head: 0,
+        capacity,
+        is_close: false,
+        senders: 1,
+        receivers: 1,
+    }));
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared, next: 0 },
+    )
+}
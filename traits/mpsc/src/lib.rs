@@ -1,6 +1,27 @@
 #![forbid(unsafe_code)]
 
-use std::{cell::RefCell, collections::VecDeque, fmt::Debug, rc::Rc};
+pub mod broadcast;
+pub mod priority;
+mod select;
+mod stats;
+pub mod sync;
+
+pub use select::Select;
+pub use stats::{CloseObserver, SendCloseObserver, Stats};
+
+use futures::{future, Sink, Stream};
+use futures_timer::Delay;
+use stats::Instrumentation;
+use std::{
+    cell::RefCell,
+    collections::VecDeque,
+    fmt::Debug,
+    future::poll_fn,
+    pin::{pin, Pin},
+    rc::Rc,
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 ////////////////////////////////////////////////////////////////////////////////
@@ -18,18 +39,37 @@ pub struct SendError<T> {
 pub struct Sender<T> {
     data: Rc<RefCell<VecDeque<T>>>,
     is_close: Rc<RefCell<bool>>,
+    waker: Rc<RefCell<Option<Waker>>>,
+    stats: Option<Rc<RefCell<Instrumentation>>>,
+    senders: Rc<RefCell<usize>>,
+    is_detached: bool,
 }
 
 impl<T> Sender<T> {
     pub fn send(&self, value: T) -> Result<(), SendError<T>> {
-        if self.is_closed() {
+        if self.is_detached || self.is_closed() {
+            self.record(Instrumentation::rejected);
             return Err(SendError { value });
         }
 
         self.data.borrow_mut().push_front(value);
+        let depth = self.data.borrow().len();
+        self.record(|stats| stats.sent(depth));
+        self.wake();
         Ok(())
     }
 
+    // `None` unless the channel was created with stats.
+    pub fn stats(&self) -> Option<Stats> {
+        self.stats.as_ref().map(|stats| stats.borrow().snapshot())
+    }
+
+    // The observer is called once, when the channel closes. Panics unless the
+    // channel was created with stats.
+    pub fn set_close_observer<F: FnMut(&Stats) + 'static>(&self, observer: F) {
+        set_close_observer(&self.stats, Box::new(observer))
+    }
+
     pub fn is_closed(&self) -> bool {
         *self.is_close.borrow()
     }
@@ -37,21 +77,175 @@ impl<T> Sender<T> {
     pub fn same_channel(&self, other: &Self) -> bool {
         self.data.as_ptr() == other.data.as_ptr()
     }
+
+    // The waker is taken out first so that it may poll the receiver again
+    // without running into the borrow.
+    fn wake(&self) {
+        let waker = self.waker.borrow_mut().take();
+        if let Some(waker) = waker {
+            waker.wake()
+        }
+    }
+
+    fn close(&self) {
+        *self.is_close.borrow_mut() = true;
+        if let Some(stats) = &self.stats {
+            stats::close(stats)
+        }
+        self.wake()
+    }
+
+    fn record(&self, update: impl FnOnce(&mut Instrumentation)) {
+        if let Some(stats) = &self.stats {
+            update(&mut stats.borrow_mut())
+        }
+    }
+
+    // Gives up this handle's share of the channel, which closes once the last
+    // sender is gone.
+    fn detach(&mut self) {
+        if self.is_detached {
+            return;
+        }
+        self.is_detached = true;
+        self.record(Instrumentation::remove_sender);
+
+        let mut senders = self.senders.borrow_mut();
+        *senders -= 1;
+        let is_last = *senders == 0;
+        drop(senders);
+        if is_last {
+            self.close()
+        }
+    }
+}
+
+fn set_close_observer(stats: &Option<Rc<RefCell<Instrumentation>>>, observer: CloseObserver) {
+    stats
+        .as_ref()
+        .expect("the channel was created without stats")
+        .borrow_mut()
+        .set_observer(observer)
 }
 
 impl<T> Clone for Sender<T> {
     fn clone(&self) -> Self {
+        self.record(Instrumentation::add_sender);
+        *self.senders.borrow_mut() += 1;
         Self {
             data: self.data.clone(),
             is_close: self.is_close.clone(),
+            waker: self.waker.clone(),
+            stats: self.stats.clone(),
+            senders: self.senders.clone(),
+            is_detached: false,
         }
     }
 }
 
 impl<T> Drop for Sender<T> {
     fn drop(&mut self) {
-        if Rc::strong_count(&self.data) <= 2 {
-            *self.is_close.borrow_mut() = true
+        self.detach()
+    }
+}
+
+// The queue is unbounded, so the sink is always ready and a closed channel is
+// reported by `start_send`. Closing the sink detaches this handle only, as
+// dropping it would; the channel closes with the last sender.
+impl<T> Sink<T> for Sender<T> {
+    type Error = SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: T) -> Result<(), Self::Error> {
+        self.send(value)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().detach();
+        Poll::Ready(Ok(()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Debug)]
+pub enum TrySendError<T> {
+    #[error("channel is full")]
+    Full(T),
+    #[error("channel is closed")]
+    Closed(T),
+}
+
+impl<T> TrySendError<T> {
+    pub fn into_inner(self) -> T {
+        match self {
+            Self::Full(value) | Self::Closed(value) => value,
+        }
+    }
+}
+
+impl<T> From<SendError<T>> for TrySendError<T> {
+    fn from(error: SendError<T>) -> Self {
+        Self::Closed(error.value)
+    }
+}
+
+pub struct BoundedSender<T> {
+    sender: Sender<T>,
+    capacity: usize,
+}
+
+impl<T> BoundedSender<T> {
+    pub fn send(&self, value: T) -> Result<(), TrySendError<T>> {
+        if self.is_closed() {
+            self.sender.record(Instrumentation::rejected);
+            return Err(TrySendError::Closed(value));
+        }
+        if self.len() >= self.capacity {
+            self.sender.record(Instrumentation::rejected);
+            return Err(TrySendError::Full(value));
+        }
+
+        Ok(self.sender.send(value)?)
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        self.sender.same_channel(&other.sender)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        self.sender.stats()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sender.data.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for BoundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            capacity: self.capacity,
         }
     }
 }
@@ -64,11 +258,16 @@ pub enum ReceiveError {
     Empty,
     #[error("channel is closed")]
     Closed,
+    #[error("receive timed out")]
+    Timeout,
 }
 
 pub struct Receiver<T> {
     data: Rc<RefCell<VecDeque<T>>>,
     is_close: Rc<RefCell<bool>>,
+    waker: Rc<RefCell<Option<Waker>>>,
+    stats: Option<Rc<RefCell<Instrumentation>>>,
+    capacity: Option<usize>,
 }
 
 impl<T> Receiver<T> {
@@ -80,11 +279,69 @@ impl<T> Receiver<T> {
             return Err(ReceiveError::Empty);
         }
 
-        Ok(self.data.borrow_mut().pop_back().unwrap())
+        let value = self.data.borrow_mut().pop_back().unwrap();
+        if let Some(stats) = &self.stats {
+            stats.borrow_mut().received(self.data.borrow().len())
+        }
+        Ok(value)
+    }
+
+    pub async fn recv_async(&mut self) -> Result<T, ReceiveError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    pub async fn recv_async_timeout(&mut self, timeout: Duration) -> Result<T, ReceiveError> {
+        let recv = pin!(self.recv_async());
+        match future::select(recv, Delay::new(timeout)).await {
+            future::Either::Left((result, _)) => result,
+            future::Either::Right(_) => Err(ReceiveError::Timeout),
+        }
+    }
+
+    pub async fn recv_async_deadline(&mut self, deadline: Instant) -> Result<T, ReceiveError> {
+        self.recv_async_timeout(deadline.saturating_duration_since(Instant::now()))
+            .await
+    }
+
+    pub fn try_iter(&mut self) -> TryIter<'_, T> {
+        TryIter { receiver: self }
+    }
+
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReceiveError>> {
+        match self.recv() {
+            Err(ReceiveError::Empty) => {
+                *self.waker.borrow_mut() = Some(cx.waker().clone());
+                Poll::Pending
+            }
+            result => Poll::Ready(result),
+        }
     }
 
     pub fn close(&mut self) {
-        *self.is_close.borrow_mut() = true
+        *self.is_close.borrow_mut() = true;
+        if let Some(stats) = &self.stats {
+            stats::close(stats)
+        }
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        self.stats.as_ref().map(|stats| stats.borrow().snapshot())
+    }
+
+    pub fn set_close_observer<F: FnMut(&Stats) + 'static>(&self, observer: F) {
+        set_close_observer(&self.stats, Box::new(observer))
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
     }
 }
 
@@ -94,17 +351,77 @@ impl<T> Drop for Receiver<T> {
     }
 }
 
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_recv(cx).map(Result::ok)
+    }
+}
+
+pub struct TryIter<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
 ////////////////////////////////////////////////////////////////////////////////
 
-pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+fn new_channel<T>(stats: Option<Instrumentation>) -> (Sender<T>, Receiver<T>) {
     let data = Rc::new(RefCell::new(VecDeque::new()));
     let is_close = Rc::new(RefCell::new(false));
+    let waker = Rc::new(RefCell::new(None));
+    let stats = stats.map(|stats| Rc::new(RefCell::new(stats)));
 
     (
         Sender {
             data: data.clone(),
             is_close: is_close.clone(),
+            waker: waker.clone(),
+            stats: stats.clone(),
+            senders: Rc::new(RefCell::new(1)),
+            is_detached: false,
+        },
+        Receiver {
+            data,
+            is_close,
+            waker,
+            stats,
+            capacity: None,
         },
-        Receiver { data, is_close },
     )
 }
+
+fn new_bounded_channel<T>(
+    capacity: usize,
+    stats: Option<Instrumentation>,
+) -> (BoundedSender<T>, Receiver<T>) {
+    assert!(capacity > 0, "capacity must be positive");
+
+    let (sender, mut receiver) = new_channel(stats);
+    receiver.capacity = Some(capacity);
+    (BoundedSender { sender, capacity }, receiver)
+}
+
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    new_channel(None)
+}
+
+// Counts what passes through the channel, see `Sender::stats`.
+pub fn channel_with_stats<T>() -> (Sender<T>, Receiver<T>) {
+    new_channel(Some(Instrumentation::new()))
+}
+
+pub fn bounded_channel<T>(capacity: usize) -> (BoundedSender<T>, Receiver<T>) {
+    new_bounded_channel(capacity, None)
+}
+
+pub fn bounded_channel_with_stats<T>(capacity: usize) -> (BoundedSender<T>, Receiver<T>) {
+    new_bounded_channel(capacity, Some(Instrumentation::new()))
+}
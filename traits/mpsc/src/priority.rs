@@ -0,0 +1,150 @@
+use std::{cell::RefCell, cmp::Ordering, collections::BinaryHeap, rc::Rc};
+
+pub use crate::{ReceiveError, SendError};
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Among equal priorities the earlier message, with the lower sequence number, wins.
+struct Entry<P, T> {
+    priority: P,
+    sequence: u64,
+    value: T,
+}
+
+impl<P: Ord, T> Ord for Entry<P, T> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
+
+impl<P: Ord, T> PartialOrd for Entry<P, T> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<P: Ord, T> PartialEq for Entry<P, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl<P: Ord, T> Eq for Entry<P, T> {}
+
+struct Queue<P, T> {
+    heap: BinaryHeap<Entry<P, T>>,
+    sequence: u64,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Sender<P, T> {
+    data: Rc<RefCell<Queue<P, T>>>,
+    is_close: Rc<RefCell<bool>>,
+}
+
+impl<P: Ord, T> Sender<P, T> {
+    pub fn send(&self, priority: P, value: T) -> Result<(), SendError<T>> {
+        if self.is_closed() {
+            return Err(SendError { value });
+        }
+
+        let mut queue = self.data.borrow_mut();
+        let sequence = queue.sequence;
+        queue.sequence += 1;
+        queue.heap.push(Entry {
+            priority,
+            sequence,
+            value,
+        });
+        Ok(())
+    }
+}
+
+impl<P, T> Sender<P, T> {
+    pub fn is_closed(&self) -> bool {
+        *self.is_close.borrow()
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.data, &other.data)
+    }
+}
+
+impl<P, T> Clone for Sender<P, T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            is_close: self.is_close.clone(),
+        }
+    }
+}
+
+impl<P, T> Drop for Sender<P, T> {
+    fn drop(&mut self) {
+        if Rc::strong_count(&self.data) <= 2 {
+            *self.is_close.borrow_mut() = true
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Receiver<P, T> {
+    data: Rc<RefCell<Queue<P, T>>>,
+    is_close: Rc<RefCell<bool>>,
+}
+
+impl<P: Ord, T> Receiver<P, T> {
+    pub fn recv(&mut self) -> Result<T, ReceiveError> {
+        self.recv_with_priority().map(|(_, value)| value)
+    }
+
+    pub fn recv_with_priority(&mut self) -> Result<(P, T), ReceiveError> {
+        match self.data.borrow_mut().heap.pop() {
+            Some(entry) => Ok((entry.priority, entry.value)),
+            None if *self.is_close.borrow() => Err(ReceiveError::Closed),
+            None => Err(ReceiveError::Empty),
+        }
+    }
+}
+
+impl<P, T> Receiver<P, T> {
+    pub fn close(&mut self) {
+        *self.is_close.borrow_mut() = true
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.borrow().heap.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<P, T> Drop for Receiver<P, T> {
+    fn drop(&mut self) {
+        self.close()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn channel<P: Ord, T>() -> (Sender<P, T>, Receiver<P, T>) {
+    let data = Rc::new(RefCell::new(Queue {
+        heap: BinaryHeap::new(),
+        sequence: 0,
+    }));
+    let is_close = Rc::new(RefCell::new(false));
+
+    (
+        Sender {
+            data: data.clone(),
+            is_close: is_close.clone(),
+        },
+        Receiver { data, is_close },
+    )
+}
@@ -0,0 +1,102 @@
+use crate::{ReceiveError, Receiver};
+
+use futures::Stream;
+use std::{
+    future::poll_fn,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Polls its receivers round-robin, starting after the one that produced the
+// previous value, so a busy channel cannot starve the others.
+pub struct Select<T> {
+    receivers: Vec<Receiver<T>>,
+    next: usize,
+}
+
+impl<T> Select<T> {
+    pub fn new() -> Self {
+        Self {
+            receivers: vec![],
+            next: 0,
+        }
+    }
+
+    pub fn add(&mut self, receiver: Receiver<T>) -> usize {
+        self.receivers.push(receiver);
+        self.receivers.len() - 1
+    }
+
+    pub fn len(&self) -> usize {
+        self.receivers.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.receivers.is_empty()
+    }
+
+    pub fn receivers(&self) -> &[Receiver<T>] {
+        &self.receivers
+    }
+
+    pub fn into_receivers(self) -> Vec<Receiver<T>> {
+        self.receivers
+    }
+
+    // Closed receivers are skipped; the error is `Closed` only once all of them are.
+    pub fn recv(&mut self) -> Result<(usize, T), ReceiveError> {
+        self.select_with(Receiver::recv)
+    }
+
+    pub async fn recv_async(&mut self) -> Result<(usize, T), ReceiveError> {
+        poll_fn(|cx| self.poll_select(cx)).await
+    }
+
+    fn poll_select(&mut self, cx: &mut Context<'_>) -> Poll<Result<(usize, T), ReceiveError>> {
+        match self.select_with(|receiver| match receiver.poll_recv(cx) {
+            Poll::Ready(result) => result,
+            Poll::Pending => Err(ReceiveError::Empty),
+        }) {
+            Err(ReceiveError::Empty) => Poll::Pending,
+            result => Poll::Ready(result),
+        }
+    }
+
+    fn select_with<F>(&mut self, mut recv: F) -> Result<(usize, T), ReceiveError>
+    where
+        F: FnMut(&mut Receiver<T>) -> Result<T, ReceiveError>,
+    {
+        let count = self.receivers.len();
+        let mut error = ReceiveError::Closed;
+
+        for offset in 0..count {
+            let index = (self.next + offset) % count;
+            match recv(&mut self.receivers[index]) {
+                Ok(value) => {
+                    self.next = (index + 1) % count;
+                    return Ok((index, value));
+                }
+                Err(ReceiveError::Closed) => {}
+                Err(other) => error = other,
+            }
+        }
+
+        Err(error)
+    }
+}
+
+impl<T> Default for Select<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Stream for Select<T> {
+    type Item = (usize, T);
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        self.get_mut().poll_select(cx).map(Result::ok)
+    }
+}
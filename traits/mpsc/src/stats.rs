@@ -0,0 +1,94 @@
+use std::cell::RefCell;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Stats {
+    pub sent: u64,
+    pub received: u64,
+    pub rejected: u64,
+    pub depth: usize,
+    pub peak_depth: usize,
+    pub senders: usize,
+    pub is_closed: bool,
+}
+
+pub type CloseObserver = Box<dyn FnMut(&Stats)>;
+
+// The thread-safe channels may close on any thread, so their observer must be `Send`.
+pub type SendCloseObserver = Box<dyn FnMut(&Stats) + Send>;
+
+// Only channels created with stats carry one of these.
+pub(crate) struct Instrumentation<O = CloseObserver> {
+    stats: Stats,
+    on_close: Option<O>,
+}
+
+impl<O> Instrumentation<O> {
+    pub(crate) fn new() -> Self {
+        Self {
+            stats: Stats {
+                senders: 1,
+                ..Stats::default()
+            },
+            on_close: None,
+        }
+    }
+
+    pub(crate) fn snapshot(&self) -> Stats {
+        self.stats
+    }
+
+    pub(crate) fn set_observer(&mut self, observer: O) {
+        self.on_close = Some(observer);
+    }
+
+    pub(crate) fn sent(&mut self, depth: usize) {
+        self.stats.sent += 1;
+        self.stats.depth = depth;
+        self.stats.peak_depth = self.stats.peak_depth.max(depth);
+    }
+
+    pub(crate) fn received(&mut self, depth: usize) {
+        self.stats.received += 1;
+        self.stats.depth = depth;
+    }
+
+    pub(crate) fn rejected(&mut self) {
+        self.stats.rejected += 1;
+    }
+
+    // A sent value that was handed back to its sender unclaimed.
+    pub(crate) fn withdrawn(&mut self, depth: usize) {
+        self.stats.sent -= 1;
+        self.stats.rejected += 1;
+        self.stats.depth = depth;
+    }
+
+    pub(crate) fn add_sender(&mut self) {
+        self.stats.senders += 1;
+    }
+
+    pub(crate) fn remove_sender(&mut self) {
+        self.stats.senders -= 1;
+    }
+
+    // Marks the channel closed and hands out the observer the first time only.
+    // The caller runs it once it has released the channel.
+    pub(crate) fn close(&mut self) -> Option<(O, Stats)> {
+        if self.stats.is_closed {
+            return None;
+        }
+        self.stats.is_closed = true;
+        self.on_close.take().map(|observer| (observer, self.stats))
+    }
+}
+
+// The observer runs after the borrow is released, so it may inspect the
+// channel it observes.
+pub(crate) fn close(instrumentation: &RefCell<Instrumentation>) {
+    let observer = instrumentation.borrow_mut().close();
+    if let Some((mut observer, stats)) = observer {
+        observer(&stats)
+    }
+}
@@ -0,0 +1,539 @@
+use futures::{future, Sink, Stream};
+use futures_timer::Delay;
+use std::{
+    collections::VecDeque,
+    future::poll_fn,
+    pin::{pin, Pin},
+    task::{Context, Poll, Waker},
+    time::{Duration, Instant},
+};
+
+// Under `--cfg loom` the channels are built on loom's primitives so that
+// tests/loom.rs can explore every interleaving of their operations.
+#[cfg(loom)]
+use loom::sync::{Arc, Condvar, Mutex, MutexGuard};
+#[cfg(not(loom))]
+use std::sync::{Arc, Condvar, Mutex, MutexGuard};
+
+pub mod oneshot;
+
+use crate::stats::Instrumentation;
+
+pub use crate::{ReceiveError, SendCloseObserver, SendError, Stats, TrySendError};
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct State<T> {
+    queue: VecDeque<T>,
+    is_close: bool,
+    senders: usize,
+    waker: Option<Waker>,
+    // Values taken so far and receivers blocked in `recv`; a rendezvous sender
+    // uses them to learn when its value was handed over. An async receiver
+    // waits while its waker is registered.
+    received: u64,
+    receiving: usize,
+    stats: Option<Instrumentation<SendCloseObserver>>,
+}
+
+impl<T> State<T> {
+    fn record(&mut self, update: impl FnOnce(&mut Instrumentation<SendCloseObserver>)) {
+        if let Some(stats) = &mut self.stats {
+            update(stats)
+        }
+    }
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    available: Condvar,
+    space: Condvar,
+    capacity: Option<usize>,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+
+    // Wakes every blocked sender and receiver as well as the async receiver.
+    // The close observer runs last, without the lock held.
+    fn close(&self) {
+        let mut state = self.lock();
+        state.is_close = true;
+        let waker = state.waker.take();
+        let observer = state.stats.as_mut().and_then(Instrumentation::close);
+        drop(state);
+        self.available.notify_all();
+        self.space.notify_all();
+        if let Some(waker) = waker {
+            waker.wake()
+        }
+        if let Some((mut observer, stats)) = observer {
+            observer(&stats)
+        }
+    }
+
+    fn stats(&self) -> Option<Stats> {
+        self.lock().stats.as_ref().map(Instrumentation::snapshot)
+    }
+
+    // Panics unless the channel was created with stats.
+    fn set_close_observer(&self, observer: SendCloseObserver) {
+        self.lock()
+            .stats
+            .as_mut()
+            .expect("the channel was created without stats")
+            .set_observer(observer)
+    }
+
+    // Wakes the blocked and the async receiver once `state` has been released.
+    fn notify_receiver(&self, mut state: MutexGuard<'_, State<T>>) {
+        let waker = state.waker.take();
+        drop(state);
+        self.available.notify_one();
+        if let Some(waker) = waker {
+            waker.wake()
+        }
+    }
+
+    fn pop(&self, state: &mut State<T>) -> Option<T> {
+        let value = state.queue.pop_front()?;
+        state.received += 1;
+        let depth = state.queue.len();
+        state.record(|stats| stats.received(depth));
+        if self.capacity.is_some() {
+            self.space.notify_all();
+        }
+        Some(value)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+    is_detached: bool,
+}
+
+impl<T> Sender<T> {
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.lock();
+        if self.is_detached || state.is_close {
+            state.record(Instrumentation::rejected);
+            return Err(SendError { value });
+        }
+
+        state.queue.push_back(value);
+        let depth = state.queue.len();
+        state.record(|stats| stats.sent(depth));
+        self.shared.notify_receiver(state);
+        Ok(())
+    }
+
+    // `None` unless the channel was created with stats.
+    pub fn stats(&self) -> Option<Stats> {
+        self.shared.stats()
+    }
+
+    // The observer is called once, when the channel closes. Panics unless the
+    // channel was created with stats.
+    pub fn set_close_observer<F: FnMut(&Stats) + Send + 'static>(&self, observer: F) {
+        self.shared.set_close_observer(Box::new(observer))
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().is_close
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.shared, &other.shared)
+    }
+
+    // Gives up this handle's share of the channel, which closes once the last
+    // sender is gone.
+    fn detach(&mut self) {
+        if self.is_detached {
+            return;
+        }
+        self.is_detached = true;
+
+        let mut state = self.shared.lock();
+        state.senders -= 1;
+        state.record(Instrumentation::remove_sender);
+        let is_last = state.senders == 0;
+        drop(state);
+        if is_last {
+            self.shared.close()
+        }
+    }
+}
+
+impl<T> Clone for Sender<T> {
+    fn clone(&self) -> Self {
+        let mut state = self.shared.lock();
+        state.senders += 1;
+        state.record(Instrumentation::add_sender);
+        drop(state);
+        Self {
+            shared: self.shared.clone(),
+            is_detached: false,
+        }
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        self.detach()
+    }
+}
+
+// The queue is unbounded, so the sink is always ready and a closed channel is
+// reported by `start_send`. Closing the sink detaches this handle only, as
+// dropping it would; the channel closes with the last sender.
+impl<T> Sink<T> for Sender<T> {
+    type Error = SendError<T>;
+
+    fn poll_ready(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn start_send(self: Pin<&mut Self>, value: T) -> Result<(), Self::Error> {
+        self.send(value)
+    }
+
+    fn poll_flush(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn poll_close(self: Pin<&mut Self>, _: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.get_mut().detach();
+        Poll::Ready(Ok(()))
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct BoundedSender<T> {
+    sender: Sender<T>,
+    capacity: usize,
+}
+
+impl<T> BoundedSender<T> {
+    // Blocks while the channel is full. With zero capacity it also waits until
+    // a receiver has taken the value.
+    pub fn send(&self, value: T) -> Result<(), SendError<T>> {
+        let shared = &self.sender.shared;
+        let mut state = shared.lock();
+        loop {
+            if state.is_close {
+                state.record(Instrumentation::rejected);
+                return Err(SendError { value });
+            }
+            if state.queue.len() < self.capacity.max(1) {
+                break;
+            }
+            state = shared.space.wait(state).unwrap();
+        }
+
+        state.queue.push_back(value);
+        let depth = state.queue.len();
+        state.record(|stats| stats.sent(depth));
+        let ticket = state.received + state.queue.len() as u64;
+        shared.notify_receiver(state);
+        if self.capacity > 0 {
+            return Ok(());
+        }
+
+        let mut state = shared.lock();
+        while state.received < ticket {
+            if state.is_close {
+                let value = state.queue.pop_back().unwrap();
+                let depth = state.queue.len();
+                state.record(|stats| stats.withdrawn(depth));
+                return Err(SendError { value });
+            }
+            state = shared.space.wait(state).unwrap();
+        }
+        Ok(())
+    }
+
+    pub fn try_send(&self, value: T) -> Result<(), TrySendError<T>> {
+        let shared = &self.sender.shared;
+        let mut state = shared.lock();
+        if state.is_close {
+            state.record(Instrumentation::rejected);
+            return Err(TrySendError::Closed(value));
+        }
+        // Without capacity a value is only accepted if a receiver is waiting for
+        // it. A pending async receiver counts until it is woken, even if its
+        // future has been dropped since.
+        let waiting = state.receiving + usize::from(state.waker.is_some());
+        if state.queue.len() >= self.capacity.max(waiting) {
+            state.record(Instrumentation::rejected);
+            return Err(TrySendError::Full(value));
+        }
+
+        state.queue.push_back(value);
+        let depth = state.queue.len();
+        state.record(|stats| stats.sent(depth));
+        shared.notify_receiver(state);
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.sender.is_closed()
+    }
+
+    pub fn same_channel(&self, other: &Self) -> bool {
+        self.sender.same_channel(&other.sender)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        self.sender.stats()
+    }
+
+    pub fn len(&self) -> usize {
+        self.sender.shared.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Clone for BoundedSender<T> {
+    fn clone(&self) -> Self {
+        Self {
+            sender: self.sender.clone(),
+            capacity: self.capacity,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    // Blocks until a value arrives. Values queued before the channel was closed
+    // are still delivered.
+    pub fn recv(&mut self) -> Result<T, ReceiveError> {
+        self.recv_until(None)
+    }
+
+    pub fn recv_timeout(&mut self, timeout: Duration) -> Result<T, ReceiveError> {
+        match Instant::now().checked_add(timeout) {
+            Some(deadline) => self.recv_deadline(deadline),
+            None => self.recv(),
+        }
+    }
+
+    pub fn recv_deadline(&mut self, deadline: Instant) -> Result<T, ReceiveError> {
+        self.recv_until(Some(deadline))
+    }
+
+    fn recv_until(&mut self, deadline: Option<Instant>) -> Result<T, ReceiveError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(value) = self.shared.pop(&mut state) {
+                return Ok(value);
+            }
+            if state.is_close {
+                return Err(ReceiveError::Closed);
+            }
+
+            let timeout = match deadline {
+                Some(deadline) => match deadline.checked_duration_since(Instant::now()) {
+                    Some(timeout) if !timeout.is_zero() => Some(timeout),
+                    _ => return Err(ReceiveError::Timeout),
+                },
+                None => None,
+            };
+            state.receiving += 1;
+            state = match timeout {
+                Some(timeout) => {
+                    self.shared
+                        .available
+                        .wait_timeout(state, timeout)
+                        .unwrap()
+                        .0
+                }
+                None => self.shared.available.wait(state).unwrap(),
+            };
+            state.receiving -= 1;
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, ReceiveError> {
+        let mut state = self.shared.lock();
+        match self.shared.pop(&mut state) {
+            Some(value) => Ok(value),
+            None if state.is_close => Err(ReceiveError::Closed),
+            None => Err(ReceiveError::Empty),
+        }
+    }
+
+    pub async fn recv_async(&mut self) -> Result<T, ReceiveError> {
+        poll_fn(|cx| self.poll_recv(cx)).await
+    }
+
+    pub async fn recv_async_timeout(&mut self, timeout: Duration) -> Result<T, ReceiveError> {
+        let recv = pin!(self.recv_async());
+        match future::select(recv, Delay::new(timeout)).await {
+            future::Either::Left((result, _)) => result,
+            future::Either::Right(_) => Err(ReceiveError::Timeout),
+        }
+    }
+
+    pub async fn recv_async_deadline(&mut self, deadline: Instant) -> Result<T, ReceiveError> {
+        self.recv_async_timeout(deadline.saturating_duration_since(Instant::now()))
+            .await
+    }
+
+    // Yields the values that are already queued without blocking.
+    pub fn try_iter(&mut self) -> TryIter<'_, T> {
+        TryIter { receiver: self }
+    }
+
+    // Blocks for every value until the channel is closed.
+    pub fn iter(&mut self) -> Iter<'_, T> {
+        Iter { receiver: self }
+    }
+
+    // The waker is registered under the same lock the senders push under, so a
+    // value sent right after the emptiness check cannot be missed.
+    fn poll_recv(&mut self, cx: &mut Context<'_>) -> Poll<Result<T, ReceiveError>> {
+        let mut state = self.shared.lock();
+        match self.shared.pop(&mut state) {
+            Some(value) => Poll::Ready(Ok(value)),
+            None if state.is_close => Poll::Ready(Err(ReceiveError::Closed)),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.shared.close()
+    }
+
+    pub fn stats(&self) -> Option<Stats> {
+        self.shared.stats()
+    }
+
+    pub fn set_close_observer<F: FnMut(&Stats) + Send + 'static>(&self, observer: F) {
+        self.shared.set_close_observer(Box::new(observer))
+    }
+
+    pub fn capacity(&self) -> Option<usize> {
+        self.shared.capacity
+    }
+
+    pub fn len(&self) -> usize {
+        self.shared.lock().queue.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close()
+    }
+}
+
+impl<T> Stream for Receiver<T> {
+    type Item = T;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<T>> {
+        self.get_mut().poll_recv(cx).map(Result::ok)
+    }
+}
+
+pub struct TryIter<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Iterator for TryIter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.try_recv().ok()
+    }
+}
+
+pub struct Iter<'a, T> {
+    receiver: &'a mut Receiver<T>,
+}
+
+impl<T> Iterator for Iter<'_, T> {
+    type Item = T;
+
+    fn next(&mut self) -> Option<T> {
+        self.receiver.recv().ok()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn new_channel<T>(
+    capacity: Option<usize>,
+    stats: Option<Instrumentation<SendCloseObserver>>,
+) -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            queue: VecDeque::new(),
+            is_close: false,
+            senders: 1,
+            waker: None,
+            received: 0,
+            receiving: 0,
+            stats,
+        }),
+        available: Condvar::new(),
+        space: Condvar::new(),
+        capacity,
+    });
+
+    (
+        Sender {
+            shared: shared.clone(),
+            is_detached: false,
+        },
+        Receiver { shared },
+    )
+}
+
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    new_channel(None, None)
+}
+
+// Counts what passes through the channel, see `Sender::stats`.
+pub fn channel_with_stats<T>() -> (Sender<T>, Receiver<T>) {
+    new_channel(None, Some(Instrumentation::new()))
+}
+
+// A zero capacity gives a rendezvous channel where every send waits for a receiver.
+pub fn bounded_channel<T>(capacity: usize) -> (BoundedSender<T>, Receiver<T>) {
+    let (sender, receiver) = new_channel(Some(capacity), None);
+    (BoundedSender { sender, capacity }, receiver)
+}
+
+pub fn bounded_channel_with_stats<T>(capacity: usize) -> (BoundedSender<T>, Receiver<T>) {
+    let (sender, receiver) = new_channel(Some(capacity), Some(Instrumentation::new()));
+    (BoundedSender { sender, capacity }, receiver)
+}
+
+pub fn rendezvous_channel<T>() -> (BoundedSender<T>, Receiver<T>) {
+    bounded_channel(0)
+}
@@ -0,0 +1,141 @@
+use super::{Arc, Condvar, Mutex, MutexGuard};
+use crate::{ReceiveError, SendError};
+
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+struct State<T> {
+    value: Option<T>,
+    is_close: bool,
+    waker: Option<Waker>,
+}
+
+struct Shared<T> {
+    state: Mutex<State<T>>,
+    available: Condvar,
+}
+
+impl<T> Shared<T> {
+    fn lock(&self) -> MutexGuard<'_, State<T>> {
+        self.state.lock().unwrap()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct Sender<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Sender<T> {
+    pub fn send(self, value: T) -> Result<(), SendError<T>> {
+        let mut state = self.shared.lock();
+        if state.is_close {
+            return Err(SendError { value });
+        }
+
+        state.value = Some(value);
+        // Dropping `self` right after closes the channel and wakes the receiver.
+        Ok(())
+    }
+
+    pub fn is_closed(&self) -> bool {
+        self.shared.lock().is_close
+    }
+}
+
+impl<T> Drop for Sender<T> {
+    fn drop(&mut self) {
+        let mut state = self.shared.lock();
+        state.is_close = true;
+        let waker = state.waker.take();
+        drop(state);
+        self.shared.available.notify_all();
+        if let Some(waker) = waker {
+            waker.wake()
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Once the sender is gone a missing value means it was dropped without sending,
+// which is reported as `Closed`.
+pub struct Receiver<T> {
+    shared: Arc<Shared<T>>,
+}
+
+impl<T> Receiver<T> {
+    pub fn recv(&mut self) -> Result<T, ReceiveError> {
+        let mut state = self.shared.lock();
+        loop {
+            if let Some(value) = state.value.take() {
+                return Ok(value);
+            }
+            if state.is_close {
+                return Err(ReceiveError::Closed);
+            }
+            state = self.shared.available.wait(state).unwrap();
+        }
+    }
+
+    pub fn try_recv(&mut self) -> Result<T, ReceiveError> {
+        let mut state = self.shared.lock();
+        match state.value.take() {
+            Some(value) => Ok(value),
+            None if state.is_close => Err(ReceiveError::Closed),
+            None => Err(ReceiveError::Empty),
+        }
+    }
+
+    pub fn close(&mut self) {
+        self.shared.lock().is_close = true
+    }
+}
+
+impl<T> Drop for Receiver<T> {
+    fn drop(&mut self) {
+        self.close()
+    }
+}
+
+impl<T> Future for Receiver<T> {
+    type Output = Result<T, ReceiveError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mut state = self.shared.lock();
+        match state.value.take() {
+            Some(value) => Poll::Ready(Ok(value)),
+            None if state.is_close => Poll::Ready(Err(ReceiveError::Closed)),
+            None => {
+                state.waker = Some(cx.waker().clone());
+                Poll::Pending
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub fn channel<T>() -> (Sender<T>, Receiver<T>) {
+    let shared = Arc::new(Shared {
+        state: Mutex::new(State {
+            value: None,
+            is_close: false,
+            waker: None,
+        }),
+        available: Condvar::new(),
+    });
+
+    (
+        Sender {
+            shared: shared.clone(),
+        },
+        Receiver { shared },
+    )
+}
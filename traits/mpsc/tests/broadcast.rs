@@ -0,0 +1,66 @@
+use mpsc::broadcast::{self, ReceiveError};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn every_receiver_sees_every_message() {
+    let (sender, mut first) = broadcast::channel(4);
+    let mut second = sender.subscribe();
+    assert_eq!(sender.receiver_count(), 2);
+
+    sender.send(1).unwrap();
+    sender.send(2).unwrap();
+    for receiver in [&mut first, &mut second] {
+        assert_eq!(receiver.recv().unwrap(), 1);
+        assert_eq!(receiver.recv().unwrap(), 2);
+        assert!(matches!(receiver.recv(), Err(ReceiveError::Empty)));
+    }
+}
+
+#[test]
+fn subscribers_start_at_the_next_message() {
+    let (sender, _receiver) = broadcast::channel(4);
+    sender.send(1).unwrap();
+
+    let mut late = sender.subscribe();
+    assert!(late.is_empty());
+    sender.send(2).unwrap();
+    assert_eq!(late.recv().unwrap(), 2);
+}
+
+#[test]
+fn lagging_receivers_resume_at_the_oldest_message() {
+    let (sender, mut slow) = broadcast::channel(2);
+    let mut fast = sender.subscribe();
+
+    for i in 0..5 {
+        sender.send(i).unwrap();
+        assert_eq!(fast.recv().unwrap(), i);
+    }
+    assert_eq!(slow.len(), 2);
+
+    assert!(matches!(slow.recv(), Err(ReceiveError::Lagged(3))));
+    assert_eq!(slow.recv().unwrap(), 3);
+    assert_eq!(slow.recv().unwrap(), 4);
+    assert!(matches!(slow.recv(), Err(ReceiveError::Empty)));
+}
+
+#[test]
+fn closed_once_drained() {
+    let (sender, mut receiver) = broadcast::channel(2);
+    sender.send(1).unwrap();
+    drop(sender);
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+}
+
+#[test]
+fn send_needs_a_receiver() {
+    let (sender, receiver) = broadcast::channel(2);
+    drop(receiver);
+    assert_eq!(sender.send(1).unwrap_err().value, 1);
+
+    let _receiver = sender.subscribe();
+    sender.send(2).unwrap();
+}
@@ -0,0 +1,163 @@
+// Exhaustive interleaving checks for the thread-safe channels. Run with
+//
+//     RUSTFLAGS="--cfg loom" cargo test --release --test loom
+//
+// Every model checks that no message is lost, none is duplicated and that
+// closing the channel is always observed. Preemptions are bounded to keep the
+// three-thread models tractable; LOOM_MAX_PREEMPTIONS overrides the bound.
+
+#![cfg(loom)]
+
+use loom::thread;
+use mpsc::sync::{self, oneshot, ReceiveError};
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn model<F: Fn() + Send + Sync + 'static>(body: F) {
+    let mut builder = loom::model::Builder::new();
+    if builder.preemption_bound.is_none() {
+        builder.preemption_bound = Some(3);
+    }
+    builder.check(body);
+}
+
+fn drain<T>(receiver: &mut sync::Receiver<T>) -> Vec<T> {
+    let mut values = vec![];
+    loop {
+        match receiver.recv() {
+            Ok(value) => values.push(value),
+            Err(ReceiveError::Closed) => return values,
+            Err(error) => panic!("unexpected error: {error}"),
+        }
+    }
+}
+
+#[test]
+fn every_message_is_received_once() {
+    model(|| {
+        let (sender, mut receiver) = sync::channel();
+        let other = sender.clone();
+
+        let first = thread::spawn(move || {
+            sender.send(1).unwrap();
+            sender.send(2).unwrap();
+        });
+        let second = thread::spawn(move || other.send(3).unwrap());
+
+        let values = drain(&mut receiver);
+        first.join().unwrap();
+        second.join().unwrap();
+
+        // Messages from one sender keep their order.
+        let ones = values
+            .iter()
+            .filter(|&&value| value != 3)
+            .collect::<Vec<_>>();
+        assert_eq!(ones, [&1, &2]);
+        let mut values = values;
+        values.sort();
+        assert_eq!(values, [1, 2, 3]);
+    });
+}
+
+#[test]
+fn last_sender_drop_wakes_receiver() {
+    model(|| {
+        let (sender, mut receiver) = sync::channel::<i32>();
+        let other = sender.clone();
+
+        let first = thread::spawn(move || drop(sender));
+        let second = thread::spawn(move || drop(other));
+
+        assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+        first.join().unwrap();
+        second.join().unwrap();
+    });
+}
+
+#[test]
+fn receiver_close_never_loses_a_message() {
+    model(|| {
+        let (sender, mut receiver) = sync::channel();
+
+        let handle = thread::spawn(move || sender.send(1).map_err(|error| error.value));
+        receiver.close();
+        let delivered = receiver.try_recv();
+        let rejected = handle.join().unwrap();
+
+        match (delivered, rejected) {
+            (Ok(1), Ok(())) => {}
+            (Err(ReceiveError::Closed), Err(1)) => {}
+            (delivered, rejected) => panic!("delivered {delivered:?}, rejected {rejected:?}"),
+        }
+    });
+}
+
+#[test]
+fn bounded_senders_block_without_losing_messages() {
+    model(|| {
+        let (sender, mut receiver) = sync::bounded_channel(1);
+        let other = sender.clone();
+
+        let first = thread::spawn(move || sender.send(1).unwrap());
+        let second = thread::spawn(move || other.send(2).unwrap());
+
+        let mut values = drain(&mut receiver);
+        first.join().unwrap();
+        second.join().unwrap();
+
+        values.sort();
+        assert_eq!(values, [1, 2]);
+    });
+}
+
+#[test]
+fn rendezvous_send_waits_for_receiver() {
+    model(|| {
+        let (sender, mut receiver) = sync::rendezvous_channel();
+
+        let handle = thread::spawn(move || {
+            sender.send(1).unwrap();
+            sender
+        });
+        assert_eq!(receiver.recv().unwrap(), 1);
+        let sender = handle.join().unwrap();
+
+        assert!(sender.is_empty());
+    });
+}
+
+#[test]
+fn rendezvous_close_returns_unclaimed_value() {
+    model(|| {
+        let (sender, mut receiver) = sync::rendezvous_channel();
+
+        let handle = thread::spawn(move || sender.send(1).map_err(|error| error.value));
+        let delivered = receiver.try_recv();
+        receiver.close();
+        let rejected = handle.join().unwrap();
+
+        match (delivered, rejected) {
+            (Ok(1), Ok(())) => {}
+            (Err(ReceiveError::Empty), Err(1)) => {}
+            (delivered, rejected) => panic!("delivered {delivered:?}, rejected {rejected:?}"),
+        }
+    });
+}
+
+#[test]
+fn oneshot_reports_dropped_sender() {
+    model(|| {
+        let (sender, mut receiver) = oneshot::channel();
+
+        let handle = thread::spawn(move || sender.send(1).unwrap());
+        assert_eq!(receiver.recv().unwrap(), 1);
+        handle.join().unwrap();
+        assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+
+        let (sender, mut receiver) = oneshot::channel::<i32>();
+        let handle = thread::spawn(move || drop(sender));
+        assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+        handle.join().unwrap();
+    });
+}
@@ -0,0 +1,63 @@
+use mpsc::priority::{self, ReceiveError};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn higher_priorities_come_first() {
+    let (sender, mut receiver) = priority::channel();
+    sender.send(1, "low").unwrap();
+    sender.send(3, "high").unwrap();
+    sender.send(2, "middle").unwrap();
+
+    assert_eq!(receiver.recv_with_priority().unwrap(), (3, "high"));
+    assert_eq!(receiver.recv_with_priority().unwrap(), (2, "middle"));
+    assert_eq!(receiver.recv_with_priority().unwrap(), (1, "low"));
+    assert!(matches!(receiver.recv(), Err(ReceiveError::Empty)));
+}
+
+#[test]
+fn equal_priorities_keep_their_order() {
+    let (sender, mut receiver) = priority::channel();
+    for i in 0..32 {
+        sender.send(i % 2, i).unwrap();
+    }
+    assert_eq!(receiver.len(), 32);
+
+    let received: Vec<_> = (0..32).map(|_| receiver.recv().unwrap()).collect();
+    let odd = (1..32).step_by(2);
+    let even = (0..32).step_by(2);
+    assert_eq!(received, odd.chain(even).collect::<Vec<_>>());
+}
+
+#[test]
+fn clones_share_the_order() {
+    let (first, mut receiver) = priority::channel();
+    let second = first.clone();
+    first.send(0, 1).unwrap();
+    second.send(0, 2).unwrap();
+    first.send(0, 3).unwrap();
+
+    let received: Vec<_> = (0..3).map(|_| receiver.recv().unwrap()).collect();
+    assert_eq!(received, [1, 2, 3]);
+}
+
+#[test]
+fn closed_once_drained() {
+    let (sender, mut receiver) = priority::channel();
+    let other = sender.clone();
+    sender.send(0, 1).unwrap();
+    drop(sender);
+    other.send(0, 2).unwrap();
+    drop(other);
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert_eq!(receiver.recv().unwrap(), 2);
+    assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+}
+
+#[test]
+fn send_fails_after_the_receiver_closes() {
+    let (sender, mut receiver) = priority::channel();
+    receiver.close();
+    assert_eq!(sender.send(0, 1).unwrap_err().value, 1);
+}
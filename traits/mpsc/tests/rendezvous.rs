@@ -0,0 +1,40 @@
+use futures::task::noop_waker;
+use mpsc::sync::{self, TrySendError};
+
+use std::{
+    future::Future,
+    pin::pin,
+    task::{Context, Poll},
+    thread,
+    time::Duration,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn try_send_needs_a_waiting_receiver() {
+    let (sender, mut receiver) = sync::rendezvous_channel();
+    assert!(matches!(sender.try_send(1), Err(TrySendError::Full(1))));
+
+    let handle = thread::spawn(move || receiver.recv().unwrap());
+    while let Err(error) = sender.try_send(2) {
+        assert!(matches!(error, TrySendError::Full(2)));
+        thread::sleep(Duration::from_millis(1));
+    }
+    assert_eq!(handle.join().unwrap(), 2);
+}
+
+#[test]
+fn try_send_hands_over_to_a_pending_async_receiver() {
+    let (sender, mut receiver) = sync::rendezvous_channel();
+    let waker = noop_waker();
+    let mut context = Context::from_waker(&waker);
+
+    let mut recv = pin!(receiver.recv_async());
+    assert!(recv.as_mut().poll(&mut context).is_pending());
+
+    sender.try_send(1).unwrap();
+    assert!(matches!(sender.try_send(2), Err(TrySendError::Full(2))));
+    assert!(matches!(recv.poll(&mut context), Poll::Ready(Ok(1))));
+    assert!(matches!(sender.try_send(3), Err(TrySendError::Full(3))));
+}
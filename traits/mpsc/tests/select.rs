@@ -0,0 +1,66 @@
+use futures::StreamExt;
+use mpsc::{ReceiveError, Select};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn busy_channels_do_not_starve_the_others() {
+    let (busy, busy_receiver) = mpsc::channel();
+    let (quiet, quiet_receiver) = mpsc::channel();
+    let mut select = Select::new();
+    assert_eq!(select.add(busy_receiver), 0);
+    assert_eq!(select.add(quiet_receiver), 1);
+
+    for i in 0..4 {
+        busy.send(i).unwrap();
+    }
+    quiet.send(10).unwrap();
+    quiet.send(11).unwrap();
+
+    let order: Vec<_> = (0..6).map(|_| select.recv().unwrap()).collect();
+    assert_eq!(order, [(0, 0), (1, 10), (0, 1), (1, 11), (0, 2), (0, 3)]);
+    assert!(matches!(select.recv(), Err(ReceiveError::Empty)));
+}
+
+#[test]
+fn closed_only_once_every_receiver_is() {
+    let (first, first_receiver) = mpsc::channel();
+    let (second, second_receiver) = mpsc::channel();
+    let mut select = Select::new();
+    select.add(first_receiver);
+    select.add(second_receiver);
+
+    first.send(1).unwrap();
+    drop(first);
+    assert_eq!(select.recv().unwrap(), (0, 1));
+    assert!(matches!(select.recv(), Err(ReceiveError::Empty)));
+
+    second.send(2).unwrap();
+    assert_eq!(select.recv().unwrap(), (1, 2));
+    drop(second);
+    assert!(matches!(select.recv(), Err(ReceiveError::Closed)));
+}
+
+#[test]
+fn an_empty_select_is_closed() {
+    let mut select = Select::<i32>::new();
+    assert!(matches!(select.recv(), Err(ReceiveError::Closed)));
+}
+
+#[test]
+fn streams_until_every_receiver_is_closed() {
+    let (first, first_receiver) = mpsc::channel();
+    let (second, second_receiver) = mpsc::channel();
+    let mut select = Select::new();
+    select.add(first_receiver);
+    select.add(second_receiver);
+
+    first.send(1).unwrap();
+    first.send(2).unwrap();
+    second.send(3).unwrap();
+    drop(first);
+    drop(second);
+
+    let values = futures::executor::block_on(select.collect::<Vec<_>>());
+    assert_eq!(values, [(0, 1), (1, 3), (0, 2)]);
+}
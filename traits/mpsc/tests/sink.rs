@@ -0,0 +1,77 @@
+use futures::{executor::block_on, SinkExt, StreamExt};
+use mpsc::ReceiveError;
+
+use std::thread;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn closing_the_sink_detaches_only_that_sender() {
+    let (mut sender, mut receiver) = mpsc::channel();
+    let other = sender.clone();
+
+    block_on(async {
+        SinkExt::send(&mut sender, 1).await.unwrap();
+        sender.close().await.unwrap();
+    });
+    assert!(sender.send(2).is_err());
+    assert!(!other.is_closed());
+    other.send(3).unwrap();
+
+    assert_eq!(receiver.recv().unwrap(), 1);
+    assert_eq!(receiver.recv().unwrap(), 3);
+    assert!(matches!(receiver.recv(), Err(ReceiveError::Empty)));
+
+    // The channel closes with the last sender, closing its sink counts too.
+    drop(other);
+    assert!(matches!(receiver.recv(), Err(ReceiveError::Closed)));
+    drop(sender);
+}
+
+#[test]
+fn forwarding_into_a_clone_keeps_the_channel_open() {
+    let (sender, mut receiver) = mpsc::channel();
+
+    block_on(futures::stream::iter([1, 2].map(Ok)).forward(sender.clone())).unwrap();
+    sender.send(3).unwrap();
+    assert_eq!(receiver.try_iter().collect::<Vec<_>>(), [1, 2, 3]);
+    assert!(!sender.is_closed());
+}
+
+#[test]
+fn closing_the_sink_ends_the_stream() {
+    let (mut sender, receiver) = mpsc::channel();
+
+    let values = block_on(async {
+        let mut values = futures::stream::iter([1, 2, 3].map(Ok));
+        sender.send_all(&mut values).await.unwrap();
+        sender.close().await.unwrap();
+        receiver.collect::<Vec<_>>().await
+    });
+    assert_eq!(values, [1, 2, 3]);
+}
+
+#[test]
+fn closing_a_sync_sink_detaches_only_that_sender() {
+    let (sender, receiver) = mpsc::sync::channel();
+    let other = sender.clone();
+
+    let handle = thread::spawn(move || {
+        let mut sender = sender;
+        block_on(async {
+            for value in 0..100 {
+                SinkExt::send(&mut sender, value).await.unwrap();
+            }
+            sender.close().await.unwrap();
+        });
+        assert!(!other.is_closed());
+        other.send(100).unwrap();
+        // Dropping the last sender wakes the pending stream.
+        drop(other);
+        sender
+    });
+
+    let values = block_on(receiver.collect::<Vec<_>>());
+    assert_eq!(values, (0..=100).collect::<Vec<_>>());
+    assert!(handle.join().unwrap().is_closed());
+}
@@ -0,0 +1,118 @@
+use mpsc::{sync, Stats};
+
+use std::{
+    cell::RefCell,
+    rc::Rc,
+    sync::{Arc, Mutex},
+    thread,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn stats_are_opt_in() {
+    let (sender, receiver) = mpsc::channel::<i32>();
+    assert_eq!(sender.stats(), None);
+    assert_eq!(receiver.stats(), None);
+
+    let (sender, receiver) = sync::channel::<i32>();
+    assert_eq!(sender.stats(), None);
+    assert_eq!(receiver.stats(), None);
+}
+
+#[test]
+#[should_panic(expected = "created without stats")]
+fn observer_needs_stats() {
+    let (_sender, receiver) = mpsc::channel::<i32>();
+    receiver.set_close_observer(|_| {});
+}
+
+#[test]
+fn counts_traffic() {
+    let (sender, mut receiver) = mpsc::channel_with_stats();
+    let seen = Rc::new(RefCell::new(None));
+    let observed = seen.clone();
+    receiver.set_close_observer(move |stats| *observed.borrow_mut() = Some(*stats));
+
+    sender.send(1).unwrap();
+    let other = sender.clone();
+    other.send(2).unwrap();
+    assert_eq!(receiver.recv().unwrap(), 1);
+    sender.send(3).unwrap();
+    assert_eq!(
+        sender.stats().unwrap(),
+        Stats {
+            sent: 3,
+            received: 1,
+            rejected: 0,
+            depth: 2,
+            peak_depth: 2,
+            senders: 2,
+            is_closed: false,
+        }
+    );
+
+    drop(sender);
+    assert_eq!(*seen.borrow(), None);
+    drop(other);
+    let closed = seen.borrow().unwrap();
+    assert!(closed.is_closed);
+    assert_eq!(closed.senders, 0);
+
+    let (sender, mut receiver) = mpsc::bounded_channel_with_stats(1);
+    sender.send(1).unwrap();
+    assert!(sender.send(2).is_err());
+    receiver.close();
+    assert!(sender.send(3).is_err());
+    assert_eq!(receiver.stats().unwrap().rejected, 2);
+}
+
+#[test]
+fn counts_sync_traffic() {
+    let (sender, mut receiver) = sync::bounded_channel_with_stats(4);
+    let seen = Arc::new(Mutex::new(None));
+    let observed = seen.clone();
+    receiver.set_close_observer(move |stats| *observed.lock().unwrap() = Some(*stats));
+
+    let handles = (0..4)
+        .map(|offset| {
+            let sender = sender.clone();
+            thread::spawn(move || {
+                for value in 0..25 {
+                    sender.send(offset * 25 + value).unwrap();
+                }
+            })
+        })
+        .collect::<Vec<_>>();
+    drop(sender);
+
+    let mut values = receiver.iter().collect::<Vec<_>>();
+    for handle in handles {
+        handle.join().unwrap();
+    }
+    values.sort();
+    assert_eq!(values, (0..100).collect::<Vec<_>>());
+
+    let stats = receiver.stats().unwrap();
+    assert_eq!((stats.sent, stats.received, stats.depth), (100, 100, 0));
+    assert!(stats.peak_depth <= 4);
+    assert!(stats.is_closed);
+
+    // The last sender closed the channel, maybe before everything was received.
+    let closed = seen.lock().unwrap().unwrap();
+    assert_eq!((closed.sent, closed.senders), (100, 0));
+}
+
+#[test]
+fn unclaimed_rendezvous_values_are_rejected() {
+    let (sender, mut receiver) = sync::bounded_channel_with_stats(0);
+    let handle = thread::spawn(move || sender.send(1).is_err());
+    while receiver.stats().unwrap().sent == 0 {
+        thread::yield_now();
+    }
+    receiver.close();
+
+    assert!(handle.join().unwrap());
+    let stats = receiver.stats().unwrap();
+    assert_eq!((stats.sent, stats.rejected, stats.depth), (0, 1, 0));
+}
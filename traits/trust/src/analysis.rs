@@ -0,0 +1,262 @@
+use crate::{Decision, History, RoundOutcome, Side};
+
+use std::fmt::Write;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SideStats {
+    pub cooperation_rate: f64,
+    pub average_payoff: f64,
+    pub exploitation_ratio: f64,
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct MatchStats {
+    pub rounds: usize,
+    pub left: SideStats,
+    pub right: SideStats,
+    pub mutual_cooperation_rate: f64,
+    pub longest_cooperation_streak: usize,
+    pub average_cooperation_streak: f64,
+    pub mistakes: usize,
+}
+
+fn ratio(count: usize, total: usize) -> f64 {
+    if total == 0 {
+        0.0
+    } else {
+        count as f64 / total as f64
+    }
+}
+
+fn side_stats(history: &History, side: Side) -> SideStats {
+    let rounds = history.len();
+    let cooperations = history
+        .iter()
+        .filter(|round| round.get(side).observed == Decision::Cooperated)
+        .count();
+    let exploit = match side {
+        Side::Left => RoundOutcome::LeftCheated,
+        Side::Right => RoundOutcome::RightCheated,
+    };
+    let exploitations = history
+        .iter()
+        .filter(|round| round.outcome == exploit)
+        .count();
+    let score = history.last().map_or(0, |round| round.score(side));
+
+    SideStats {
+        cooperation_rate: ratio(cooperations, rounds),
+        average_payoff: if rounds == 0 {
+            0.0
+        } else {
+            score as f64 / rounds as f64
+        },
+        exploitation_ratio: ratio(exploitations, rounds),
+    }
+}
+
+impl MatchStats {
+    pub fn new(history: &History) -> Self {
+        let mut streaks = vec![];
+        let mut streak = 0;
+        for round in history {
+            if round.outcome == RoundOutcome::BothCooperated {
+                streak += 1;
+            } else if streak > 0 {
+                streaks.push(streak);
+                streak = 0;
+            }
+        }
+        if streak > 0 {
+            streaks.push(streak);
+        }
+
+        let mutual_cooperations = streaks.iter().sum();
+
+        Self {
+            rounds: history.len(),
+            left: side_stats(history, Side::Left),
+            right: side_stats(history, Side::Right),
+            mutual_cooperation_rate: ratio(mutual_cooperations, history.len()),
+            longest_cooperation_streak: streaks.iter().copied().max().unwrap_or(0),
+            average_cooperation_streak: ratio(mutual_cooperations, streaks.len()),
+            mistakes: history
+                .iter()
+                .map(|round| round.left.is_mistake() as usize + round.right.is_mistake() as usize)
+                .sum(),
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub lower: f64,
+    pub upper: f64,
+}
+
+// Two-sided 95% quantiles of Student's t-distribution for 1..=30 degrees of freedom.
+const T_QUANTILES: [f64; 30] = [
+    12.706, 4.303, 3.182, 2.776, 2.571, 2.447, 2.365, 2.306, 2.262, 2.228, 2.201, 2.179, 2.160,
+    2.145, 2.131, 2.120, 2.110, 2.101, 2.093, 2.086, 2.080, 2.074, 2.069, 2.064, 2.060, 2.056,
+    2.052, 2.048, 2.045, 2.042,
+];
+
+impl Estimate {
+    pub fn new(samples: &[f64]) -> Self {
+        let count = samples.len();
+        if count == 0 {
+            return Self::default();
+        }
+
+        let mean = samples.iter().sum::<f64>() / count as f64;
+        if count == 1 {
+            return Self {
+                mean,
+                std_dev: 0.0,
+                lower: mean,
+                upper: mean,
+            };
+        }
+
+        let variance = samples
+            .iter()
+            .map(|sample| (sample - mean).powi(2))
+            .sum::<f64>()
+            / (count - 1) as f64;
+        let std_dev = variance.sqrt();
+        let quantile = T_QUANTILES.get(count - 2).copied().unwrap_or(1.96);
+        let margin = quantile * std_dev / (count as f64).sqrt();
+
+        Self {
+            mean,
+            std_dev,
+            lower: mean - margin,
+            upper: mean + margin,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+const METRICS: [&str; 10] = [
+    "left_cooperation_rate",
+    "right_cooperation_rate",
+    "left_average_payoff",
+    "right_average_payoff",
+    "left_exploitation_ratio",
+    "right_exploitation_ratio",
+    "mutual_cooperation_rate",
+    "longest_cooperation_streak",
+    "average_cooperation_streak",
+    "mistakes",
+];
+
+fn metric_values(stats: &MatchStats) -> [f64; 10] {
+    [
+        stats.left.cooperation_rate,
+        stats.right.cooperation_rate,
+        stats.left.average_payoff,
+        stats.right.average_payoff,
+        stats.left.exploitation_ratio,
+        stats.right.exploitation_ratio,
+        stats.mutual_cooperation_rate,
+        stats.longest_cooperation_streak as f64,
+        stats.average_cooperation_streak,
+        stats.mistakes as f64,
+    ]
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Report {
+    matches: Vec<MatchStats>,
+}
+
+impl Report {
+    pub fn new<'a, I: IntoIterator<Item = &'a History>>(histories: I) -> Self {
+        Self {
+            matches: histories.into_iter().map(MatchStats::new).collect(),
+        }
+    }
+
+    pub fn matches(&self) -> &[MatchStats] {
+        &self.matches
+    }
+
+    pub fn summary(&self) -> Vec<(&'static str, Estimate)> {
+        let values = self.matches.iter().map(metric_values).collect::<Vec<_>>();
+
+        METRICS
+            .iter()
+            .enumerate()
+            .map(|(metric, name)| {
+                let samples = values.iter().map(|row| row[metric]).collect::<Vec<_>>();
+                (*name, Estimate::new(&samples))
+            })
+            .collect()
+    }
+
+    // One row per match, then after a blank line one row per metric with its
+    // summary estimate.
+    pub fn to_csv(&self) -> String {
+        let mut csv = format!("match,rounds,{}\n", METRICS.join(","));
+        for (index, stats) in self.matches.iter().enumerate() {
+            write!(csv, "{index},{}", stats.rounds).unwrap();
+            for value in metric_values(stats) {
+                write!(csv, ",{value}").unwrap();
+            }
+            csv.push('\n');
+        }
+
+        csv.push_str("\nmetric,mean,std_dev,lower,upper\n");
+        for (name, estimate) in self.summary() {
+            writeln!(
+                csv,
+                "{name},{},{},{},{}",
+                estimate.mean, estimate.std_dev, estimate.lower, estimate.upper
+            )
+            .unwrap();
+        }
+        csv
+    }
+
+    pub fn to_json(&self) -> String {
+        let mut json = String::from("{\n  \"matches\": [");
+        for (index, stats) in self.matches.iter().enumerate() {
+            let separator = if index == 0 { "" } else { "," };
+            write!(json, "{separator}\n    {{\"rounds\": {}", stats.rounds).unwrap();
+            for (name, value) in METRICS.iter().zip(metric_values(stats)) {
+                write!(json, ", \"{name}\": {}", json_number(value)).unwrap();
+            }
+            json.push('}');
+        }
+        json.push_str("\n  ],\n  \"summary\": {");
+        for (index, (name, estimate)) in self.summary().into_iter().enumerate() {
+            let separator = if index == 0 { "" } else { "," };
+            write!(
+                json,
+                "{separator}\n    \"{name}\": {{\"mean\": {}, \"std_dev\": {}, \"lower\": {}, \"upper\": {}}}",
+                json_number(estimate.mean),
+                json_number(estimate.std_dev),
+                json_number(estimate.lower),
+                json_number(estimate.upper),
+            )
+            .unwrap();
+        }
+        json.push_str("\n  }\n}\n");
+        json
+    }
+}
+
+fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        value.to_string()
+    } else {
+        "null".to_string()
+    }
+}
@@ -0,0 +1,81 @@
+use crate::rng::{self, Rng};
+
+////////////////////////////////////////////////////////////////////////////////
+
+// After every round the match goes on with probability `probability`, so the
+// number of rounds is geometric and no player can tell which round is the last.
+#[derive(Clone, Debug)]
+pub struct Continuation {
+    probability: f64,
+    seed: u64,
+    max_rounds: Option<u32>,
+    rng: Rng,
+}
+
+impl Continuation {
+    pub fn new(probability: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..1.0).contains(&probability),
+            "continuation probability must be within [0, 1)"
+        );
+
+        Self {
+            probability,
+            seed,
+            max_rounds: None,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn with_max_rounds(mut self, max_rounds: u32) -> Self {
+        assert!(max_rounds > 0, "a match has at least one round");
+        self.max_rounds = Some(max_rounds);
+        self
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn max_rounds(&self) -> Option<u32> {
+        self.max_rounds
+    }
+
+    pub fn expected_rounds(&self) -> f64 {
+        match self.max_rounds {
+            None => 1.0 / (1.0 - self.probability),
+            Some(max_rounds) => {
+                (1.0 - self.probability.powi(max_rounds as i32)) / (1.0 - self.probability)
+            }
+        }
+    }
+
+    pub fn fork(&self, stream: u64) -> Self {
+        let seed = rng::fork(self.seed, stream);
+        Self {
+            rng: Rng::new(seed),
+            seed,
+            ..self.clone()
+        }
+    }
+
+    pub fn proceed(&mut self, played_rounds: u32) -> bool {
+        if self
+            .max_rounds
+            .is_some_and(|max_rounds| played_rounds >= max_rounds)
+        {
+            return false;
+        }
+        self.rng.gen_bool(self.probability)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct MatchLength {
+    pub expected: f64,
+    pub realized: u32,
+}
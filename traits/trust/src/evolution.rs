@@ -0,0 +1,178 @@
+use crate::{
+    rng::Rng, tournament::play_pairing, Agent, AgentFactory, Continuation, Noise, PayoffMatrix,
+};
+
+use std::cmp::Reverse;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct Population {
+    species: Vec<(String, AgentFactory)>,
+    counts: Vec<usize>,
+}
+
+impl Population {
+    pub fn new() -> Self {
+        Self {
+            species: vec![],
+            counts: vec![],
+        }
+    }
+
+    pub fn add_species<S, F>(&mut self, name: S, count: usize, factory: F) -> &mut Self
+    where
+        S: Into<String>,
+        F: Fn() -> Box<dyn Agent> + 'static,
+    {
+        self.species.push((name.into(), Box::new(factory)));
+        self.counts.push(count);
+        self
+    }
+
+    pub fn size(&self) -> usize {
+        self.counts.iter().sum()
+    }
+
+    pub fn count(&self, name: &str) -> Option<usize> {
+        self.species
+            .iter()
+            .position(|(species, _)| species == name)
+            .map(|index| self.counts[index])
+    }
+
+    pub fn composition(&self) -> Vec<(String, usize)> {
+        self.species
+            .iter()
+            .zip(&self.counts)
+            .map(|((name, _), count)| (name.clone(), *count))
+            .collect()
+    }
+
+    fn individuals(&self) -> Vec<usize> {
+        self.counts
+            .iter()
+            .enumerate()
+            .flat_map(|(species, count)| std::iter::repeat_n(species, *count))
+            .collect()
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Generation {
+    pub number: usize,
+    pub composition: Vec<(String, usize)>,
+}
+
+pub struct Evolution {
+    population: Population,
+    rounds: u32,
+    replaced: usize,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    continuation: Option<Continuation>,
+    rng: Rng,
+    generation: usize,
+}
+
+impl Evolution {
+    pub fn new(population: Population, rounds: u32, replaced: usize, seed: u64) -> Self {
+        assert!(
+            2 * replaced <= population.size(),
+            "cannot replace more than half of the population"
+        );
+
+        Self {
+            population,
+            rounds,
+            replaced,
+            payoffs: PayoffMatrix::default(),
+            noise: None,
+            continuation: None,
+            rng: Rng::new(seed),
+            generation: 0,
+        }
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    pub fn set_noise(&mut self, noise: Noise) -> &mut Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    pub fn set_continuation(&mut self, continuation: Continuation) -> &mut Self {
+        self.continuation = Some(continuation);
+        self
+    }
+
+    pub fn population(&self) -> &Population {
+        &self.population
+    }
+
+    pub fn generation(&self) -> Generation {
+        Generation {
+            number: self.generation,
+            composition: self.population.composition(),
+        }
+    }
+
+    pub fn run(&mut self, generations: usize) -> Vec<Generation> {
+        let mut report = vec![self.generation()];
+        for _ in 0..generations {
+            report.push(self.step());
+        }
+        report
+    }
+
+    pub fn step(&mut self) -> Generation {
+        let individuals = self.population.individuals();
+        let scores = self.play_tournament(&individuals);
+
+        // Shuffling before the stable sort breaks ties randomly but reproducibly.
+        let mut ranking = (0..individuals.len()).collect::<Vec<_>>();
+        self.rng.shuffle(&mut ranking);
+        ranking.sort_by_key(|&individual| Reverse(scores[individual]));
+
+        for &individual in &ranking[..self.replaced] {
+            self.population.counts[individuals[individual]] += 1;
+        }
+        for &individual in &ranking[ranking.len() - self.replaced..] {
+            self.population.counts[individuals[individual]] -= 1;
+        }
+
+        self.generation += 1;
+        self.generation()
+    }
+
+    fn play_tournament(&self, individuals: &[usize]) -> Vec<i32> {
+        let mut scores = vec![0; individuals.len()];
+
+        for left in 0..individuals.len() {
+            for right in left + 1..individuals.len() {
+                let stream = (self.generation * individuals.len() + left) * individuals.len();
+                let stream = (stream + right) as u64;
+                let pairing = play_pairing(
+                    (self.population.species[individuals[left]].1)(),
+                    (self.population.species[individuals[right]].1)(),
+                    self.rounds,
+                    self.payoffs,
+                    self.noise.as_ref().map(|noise| noise.fork(stream)),
+                    self.continuation
+                        .as_ref()
+                        .map(|continuation| continuation.fork(stream)),
+                );
+
+                scores[left] += pairing.left_score;
+                scores[right] += pairing.right_score;
+            }
+        }
+
+        scores
+    }
+}
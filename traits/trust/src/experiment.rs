@@ -0,0 +1,606 @@
+use crate::{
+    rng, Continuation, Evolution, Game, GameFamily, Noise, PayoffError, PayoffMatrix, Population,
+    ScriptError, StrategyError, StrategyRegistry, Tournament,
+};
+
+use std::{
+    collections::HashSet,
+    fmt::{self, Write as _},
+    fs, io,
+    path::{Path, PathBuf},
+    str::FromStr,
+};
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Debug)]
+pub enum ExperimentError {
+    #[error("line {line}: expected \"key = value\"")]
+    Syntax { line: usize },
+    #[error("line {line}: unknown key {key:?}")]
+    UnknownKey { line: usize, key: String },
+    #[error("line {line}: key {key:?} is set twice")]
+    DuplicateKey { line: usize, key: String },
+    #[error("line {line}: invalid value {value:?} for {key:?}, expected {expected}")]
+    InvalidValue {
+        line: usize,
+        key: String,
+        value: String,
+        expected: &'static str,
+    },
+    #[error("missing required key {0:?}")]
+    MissingKey(&'static str),
+    #[error("{0}")]
+    Invalid(String),
+    #[error(transparent)]
+    Payoff(#[from] PayoffError),
+    #[error(transparent)]
+    Strategy(#[from] StrategyError),
+    #[error("script {name:?}: {error}")]
+    Script { name: String, error: ScriptError },
+    #[error("{}: {error}", .path.display())]
+    Io { path: PathBuf, error: io::Error },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Mode {
+    Match,
+    Tournament,
+    Evolution,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Experiment {
+    pub mode: Mode,
+    pub agents: Vec<String>,
+    pub counts: Vec<usize>,
+    pub rounds: u32,
+    pub payoffs: PayoffMatrix,
+    pub noise: Option<f64>,
+    pub continuation: Option<f64>,
+    pub seed: u64,
+    pub generations: usize,
+    pub replaced: usize,
+    pub scripts: Vec<(String, String)>,
+    pub output: Option<String>,
+}
+
+const KEYS: [&str; 12] = [
+    "mode",
+    "agents",
+    "counts",
+    "rounds",
+    "payoffs",
+    "noise",
+    "continuation",
+    "seed",
+    "generations",
+    "replaced",
+    "output",
+    "script.<name>",
+];
+
+// Every random stream of an experiment gets its own seed forked from `seed`, so
+// that e.g. the noise never replays the decisions of a random agent.
+const NOISE_STREAM: u64 = 0;
+const CONTINUATION_STREAM: u64 = 1;
+const SHUFFLE_STREAM: u64 = 2;
+const AGENT_STREAM: u64 = 3;
+
+fn invalid(line: usize, key: &str, value: &str, expected: &'static str) -> ExperimentError {
+    ExperimentError::InvalidValue {
+        line,
+        key: key.to_string(),
+        value: value.to_string(),
+        expected,
+    }
+}
+
+fn parse_value<T: FromStr>(
+    line: usize,
+    key: &str,
+    value: &str,
+    expected: &'static str,
+) -> Result<T, ExperimentError> {
+    value
+        .parse()
+        .map_err(|_| invalid(line, key, value, expected))
+}
+
+fn parse_list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|item| item.trim().to_string())
+        .filter(|item| !item.is_empty())
+        .collect()
+}
+
+fn parse_payoffs(line: usize, value: &str) -> Result<PayoffMatrix, ExperimentError> {
+    const EXPECTED: &str = "\"<family> <T> <R> <P> <S>\" or \"snowdrift <benefit> <cost>\"";
+
+    let mut fields = value.split_whitespace();
+    let family = match fields.next() {
+        Some("prisoners-dilemma") => GameFamily::PrisonersDilemma,
+        Some("stag-hunt") => GameFamily::StagHunt,
+        Some("chicken") => GameFamily::Chicken,
+        Some("snowdrift") => GameFamily::Snowdrift,
+        _ => return Err(invalid(line, "payoffs", value, EXPECTED)),
+    };
+    let numbers = fields
+        .map(|field| parse_value::<i32>(line, "payoffs", field, EXPECTED))
+        .collect::<Result<Vec<_>, _>>()?;
+
+    let matrix = match (family, numbers.as_slice()) {
+        (GameFamily::Snowdrift, &[benefit, cost]) => PayoffMatrix::snowdrift(benefit, cost)?,
+        (family, &[temptation, reward, punishment, sucker]) => {
+            PayoffMatrix::new(family, temptation, reward, punishment, sucker)?
+        }
+        _ => return Err(invalid(line, "payoffs", value, EXPECTED)),
+    };
+    Ok(matrix)
+}
+
+impl FromStr for Experiment {
+    type Err = ExperimentError;
+
+    fn from_str(source: &str) -> Result<Self, Self::Err> {
+        let mut experiment = Experiment {
+            mode: Mode::Match,
+            agents: vec![],
+            counts: vec![],
+            rounds: 0,
+            payoffs: PayoffMatrix::default(),
+            noise: None,
+            continuation: None,
+            seed: 0,
+            generations: 0,
+            replaced: 0,
+            scripts: vec![],
+            output: None,
+        };
+        let mut seen = HashSet::new();
+
+        for (index, line) in source.lines().enumerate() {
+            let line_number = index + 1;
+            let content = line.split('#').next().unwrap_or_default().trim();
+            if content.is_empty() {
+                continue;
+            }
+
+            let (key, value) = content
+                .split_once('=')
+                .map(|(key, value)| (key.trim(), value.trim()))
+                .ok_or(ExperimentError::Syntax { line: line_number })?;
+            if !seen.insert(key.to_string()) {
+                return Err(ExperimentError::DuplicateKey {
+                    line: line_number,
+                    key: key.to_string(),
+                });
+            }
+
+            match key {
+                "mode" => {
+                    experiment.mode = match value {
+                        "match" => Mode::Match,
+                        "tournament" => Mode::Tournament,
+                        "evolution" => Mode::Evolution,
+                        _ => {
+                            return Err(invalid(
+                                line_number,
+                                key,
+                                value,
+                                "\"match\", \"tournament\" or \"evolution\"",
+                            ))
+                        }
+                    }
+                }
+                "agents" => experiment.agents = parse_list(value),
+                "counts" => {
+                    experiment.counts = parse_list(value)
+                        .iter()
+                        .map(|count| parse_value(line_number, key, count, "an agent count"))
+                        .collect::<Result<_, _>>()?
+                }
+                "rounds" => {
+                    experiment.rounds = parse_value(line_number, key, value, "a round count")?
+                }
+                "payoffs" => experiment.payoffs = parse_payoffs(line_number, value)?,
+                "noise" => {
+                    let noise: f64 = parse_value(line_number, key, value, "a probability")?;
+                    if !(0.0..=1.0).contains(&noise) {
+                        return Err(invalid(line_number, key, value, "a probability"));
+                    }
+                    experiment.noise = Some(noise);
+                }
+                "continuation" => {
+                    let continuation: f64 =
+                        parse_value(line_number, key, value, "a probability below 1")?;
+                    if !(0.0..1.0).contains(&continuation) {
+                        return Err(invalid(line_number, key, value, "a probability below 1"));
+                    }
+                    experiment.continuation = Some(continuation);
+                }
+                "seed" => experiment.seed = parse_value(line_number, key, value, "an integer")?,
+                "generations" => {
+                    experiment.generations =
+                        parse_value(line_number, key, value, "a generation count")?
+                }
+                "replaced" => {
+                    experiment.replaced = parse_value(line_number, key, value, "an agent count")?
+                }
+                "output" => experiment.output = Some(value.to_string()),
+                _ => match key.strip_prefix("script.") {
+                    Some(name) if !name.is_empty() => experiment
+                        .scripts
+                        .push((name.to_string(), value.to_string())),
+                    _ => {
+                        return Err(ExperimentError::UnknownKey {
+                            line: line_number,
+                            key: key.to_string(),
+                        })
+                    }
+                },
+            }
+        }
+
+        for key in ["mode", "agents", "rounds"] {
+            if !seen.contains(key) {
+                return Err(ExperimentError::MissingKey(key));
+            }
+        }
+        experiment.validate()?;
+
+        Ok(experiment)
+    }
+}
+
+// Writes the experiment back in the format `from_str` reads, leaving out keys
+// that are at their defaults.
+impl fmt::Display for Experiment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mode = match self.mode {
+            Mode::Match => "match",
+            Mode::Tournament => "tournament",
+            Mode::Evolution => "evolution",
+        };
+        writeln!(f, "mode = {mode}")?;
+        writeln!(f, "agents = {}", self.agents.join(", "))?;
+        if !self.counts.is_empty() {
+            let counts = self
+                .counts
+                .iter()
+                .map(|count| count.to_string())
+                .collect::<Vec<_>>();
+            writeln!(f, "counts = {}", counts.join(", "))?;
+        }
+        writeln!(f, "rounds = {}", self.rounds)?;
+
+        let family = match self.payoffs.family() {
+            GameFamily::PrisonersDilemma => "prisoners-dilemma",
+            GameFamily::StagHunt => "stag-hunt",
+            GameFamily::Chicken => "chicken",
+            GameFamily::Snowdrift => "snowdrift",
+        };
+        writeln!(
+            f,
+            "payoffs = {family} {} {} {} {}",
+            self.payoffs.temptation(),
+            self.payoffs.reward(),
+            self.payoffs.punishment(),
+            self.payoffs.sucker()
+        )?;
+
+        if let Some(noise) = self.noise {
+            writeln!(f, "noise = {noise}")?;
+        }
+        if let Some(continuation) = self.continuation {
+            writeln!(f, "continuation = {continuation}")?;
+        }
+        writeln!(f, "seed = {}", self.seed)?;
+        if self.generations > 0 {
+            writeln!(f, "generations = {}", self.generations)?;
+        }
+        if self.replaced > 0 {
+            writeln!(f, "replaced = {}", self.replaced)?;
+        }
+        for (name, path) in &self.scripts {
+            writeln!(f, "script.{name} = {path}")?;
+        }
+        if let Some(output) = &self.output {
+            writeln!(f, "output = {output}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Experiment {
+    pub fn keys() -> &'static [&'static str] {
+        &KEYS
+    }
+
+    fn validate(&self) -> Result<(), ExperimentError> {
+        let error = |message: String| Err(ExperimentError::Invalid(message));
+
+        match self.mode {
+            Mode::Match if self.agents.len() != 2 => error(format!(
+                "a match needs exactly 2 agents, found {}",
+                self.agents.len()
+            )),
+            Mode::Tournament if self.agents.len() < 2 => error(format!(
+                "a tournament needs at least 2 agents, found {}",
+                self.agents.len()
+            )),
+            Mode::Evolution if self.counts.len() != self.agents.len() => error(format!(
+                "evolution needs a count for each of the {} agents, found {}",
+                self.agents.len(),
+                self.counts.len()
+            )),
+            Mode::Evolution if 2 * self.replaced > self.counts.iter().sum::<usize>() => {
+                error("cannot replace more than half of the population".to_string())
+            }
+            _ if self.continuation.is_some() && self.rounds == 0 => {
+                error("rounds caps the match length and must be positive".to_string())
+            }
+            _ if self.largest_score() > i128::from(i32::MAX) => error(format!(
+                "scores of up to {} do not fit in 32 bits, lower the payoffs or rounds",
+                self.largest_score()
+            )),
+            _ => Ok(()),
+        }
+    }
+
+    // A bound on the magnitude of any score: the largest payoff in every round of
+    // every match a player takes part in.
+    fn largest_score(&self) -> i128 {
+        let matches = match self.mode {
+            Mode::Match => 1,
+            Mode::Tournament => self.agents.len(),
+            Mode::Evolution => self.counts.iter().sum(),
+        };
+        let payoffs = &self.payoffs;
+        let largest_payoff = [
+            payoffs.temptation(),
+            payoffs.reward(),
+            payoffs.punishment(),
+            payoffs.sucker(),
+        ]
+        .into_iter()
+        .map(|payoff| i128::from(payoff).abs())
+        .max()
+        .unwrap_or_default();
+
+        largest_payoff * i128::from(self.rounds) * matches as i128
+    }
+
+    // Script paths are resolved relative to `base`, normally the experiment file's directory.
+    pub fn registry(&self, base: &Path) -> Result<StrategyRegistry, ExperimentError> {
+        let mut registry = StrategyRegistry::with_payoffs(self.payoffs);
+
+        for (name, path) in &self.scripts {
+            let path = base.join(path);
+            let source = fs::read_to_string(&path).map_err(|error| ExperimentError::Io {
+                path: path.clone(),
+                error,
+            })?;
+            registry
+                .register_script(name.clone(), &source)
+                .map_err(|error| ExperimentError::Script {
+                    name: name.clone(),
+                    error,
+                })?;
+        }
+
+        Ok(registry)
+    }
+
+    // Resolved against the same `base` as the scripts.
+    pub fn output_path(&self, base: &Path) -> Option<PathBuf> {
+        self.output.as_ref().map(|output| base.join(output))
+    }
+
+    pub fn run(&self, registry: &StrategyRegistry) -> Result<ExperimentReport, ExperimentError> {
+        match self.mode {
+            Mode::Match => self.run_match(registry),
+            Mode::Tournament => self.run_tournament(registry),
+            Mode::Evolution => self.run_evolution(registry),
+        }
+    }
+
+    fn stream_seed(&self, stream: u64) -> u64 {
+        rng::fork(self.seed, stream)
+    }
+
+    fn agent_seed(&self, index: usize) -> u64 {
+        self.stream_seed(AGENT_STREAM + index as u64)
+    }
+
+    fn noise(&self) -> Option<Noise> {
+        self.noise
+            .map(|probability| Noise::new(probability, self.stream_seed(NOISE_STREAM)))
+    }
+
+    // With a continuation probability `rounds` only caps the match length.
+    fn continuation(&self) -> Option<Continuation> {
+        self.continuation.map(|probability| {
+            Continuation::new(probability, self.stream_seed(CONTINUATION_STREAM))
+                .with_max_rounds(self.rounds)
+        })
+    }
+
+    fn run_match(&self, registry: &StrategyRegistry) -> Result<ExperimentReport, ExperimentError> {
+        let mut game = Game::with_payoffs(
+            registry.create(&self.agents[0], self.agent_seed(0))?,
+            registry.create(&self.agents[1], self.agent_seed(1))?,
+            self.payoffs,
+        );
+        if let Some(noise) = self.noise() {
+            game.set_noise(noise);
+        }
+        let length = match self.continuation() {
+            Some(mut continuation) => Some(game.play_with_continuation(&mut continuation)),
+            None => {
+                game.set_total_rounds(self.rounds);
+                for _ in 0..self.rounds {
+                    game.play_round();
+                }
+                None
+            }
+        };
+
+        let mut summary = String::new();
+        for (index, round) in game.history().iter().enumerate() {
+            writeln!(
+                summary,
+                "{:>5}  {:<10} {:<10} {:>6} {:>6}",
+                index + 1,
+                format!("{:?}", round.left.observed),
+                format!("{:?}", round.right.observed),
+                round.left_score,
+                round.right_score,
+            )
+            .unwrap();
+        }
+        writeln!(
+            summary,
+            "{}: {}, {}: {}",
+            self.agents[0],
+            game.left_score(),
+            self.agents[1],
+            game.right_score()
+        )
+        .unwrap();
+        if let Some(length) = length {
+            writeln!(
+                summary,
+                "rounds: {} realized, {:.2} expected",
+                length.realized, length.expected
+            )
+            .unwrap();
+        }
+
+        Ok(ExperimentReport {
+            summary,
+            data: game.history().to_string(),
+        })
+    }
+
+    fn run_tournament(
+        &self,
+        registry: &StrategyRegistry,
+    ) -> Result<ExperimentReport, ExperimentError> {
+        let mut tournament = Tournament::new(self.rounds);
+        tournament.set_payoffs(self.payoffs);
+        if let Some(noise) = self.noise() {
+            tournament.set_noise(noise);
+        }
+        if let Some(continuation) = self.continuation() {
+            tournament.set_continuation(continuation);
+        }
+        for (index, agent) in self.agents.iter().enumerate() {
+            let seed = self.agent_seed(index);
+            tournament.add_agent(agent.clone(), registry.factory(agent, seed)?);
+        }
+
+        let result = tournament.play();
+        let leaderboard = result.leaderboard();
+
+        let mut summary = String::new();
+        let mut data = String::from("rank,name,score,wins,draws,losses\n");
+        for (index, standing) in leaderboard.iter().enumerate() {
+            writeln!(
+                summary,
+                "{:>3}. {:<24} {:>8}  {}W {}D {}L",
+                index + 1,
+                standing.name,
+                standing.score,
+                standing.wins,
+                standing.draws,
+                standing.losses
+            )
+            .unwrap();
+            writeln!(
+                data,
+                "{},{},{},{},{},{}",
+                index + 1,
+                standing.name,
+                standing.score,
+                standing.wins,
+                standing.draws,
+                standing.losses
+            )
+            .unwrap();
+        }
+
+        if let Some(continuation) = self.continuation() {
+            writeln!(
+                summary,
+                "rounds per match: {:.2} realized, {:.2} expected",
+                result.average_rounds(),
+                continuation.expected_rounds()
+            )
+            .unwrap();
+        }
+
+        Ok(ExperimentReport { summary, data })
+    }
+
+    fn run_evolution(
+        &self,
+        registry: &StrategyRegistry,
+    ) -> Result<ExperimentReport, ExperimentError> {
+        let mut population = Population::new();
+        for (index, (agent, count)) in self.agents.iter().zip(&self.counts).enumerate() {
+            let seed = self.agent_seed(index);
+            population.add_species(agent.clone(), *count, registry.factory(agent, seed)?);
+        }
+
+        let mut evolution = Evolution::new(
+            population,
+            self.rounds,
+            self.replaced,
+            self.stream_seed(SHUFFLE_STREAM),
+        );
+        evolution.set_payoffs(self.payoffs);
+        if let Some(noise) = self.noise() {
+            evolution.set_noise(noise);
+        }
+        if let Some(continuation) = self.continuation() {
+            evolution.set_continuation(continuation);
+        }
+
+        let mut summary = String::new();
+        let mut data = format!("generation,{}\n", self.agents.join(","));
+        for generation in evolution.run(self.generations) {
+            let counts = generation
+                .composition
+                .iter()
+                .map(|(_, count)| count.to_string())
+                .collect::<Vec<_>>();
+            let composition = generation
+                .composition
+                .iter()
+                .map(|(name, count)| format!("{name}: {count}"))
+                .collect::<Vec<_>>();
+
+            writeln!(
+                summary,
+                "{:>5}  {}",
+                generation.number,
+                composition.join(", ")
+            )
+            .unwrap();
+            writeln!(data, "{},{}", generation.number, counts.join(",")).unwrap();
+        }
+
+        Ok(ExperimentReport { summary, data })
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ExperimentReport {
+    pub summary: String,
+    pub data: String,
+}
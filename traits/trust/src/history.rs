@@ -0,0 +1,342 @@
+use crate::{Decision, RoundOutcome};
+
+use std::{fmt, str::FromStr};
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Side {
+    Left,
+    Right,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Move {
+    pub intended: Decision,
+    pub observed: Decision,
+}
+
+impl Move {
+    pub fn new(intended: Decision, observed: Decision) -> Self {
+        Self { intended, observed }
+    }
+
+    pub fn is_mistake(&self) -> bool {
+        self.intended != self.observed
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Round {
+    pub left: Move,
+    pub right: Move,
+    pub outcome: RoundOutcome,
+    pub left_score: i32,
+    pub right_score: i32,
+}
+
+impl Round {
+    pub fn get(&self, side: Side) -> Move {
+        match side {
+            Side::Left => self.left,
+            Side::Right => self.right,
+        }
+    }
+
+    pub fn score(&self, side: Side) -> i32 {
+        match side {
+            Side::Left => self.left_score,
+            Side::Right => self.right_score,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+const HEADER: &str = "trust-history v1";
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum HistoryError {
+    #[error("missing \"{HEADER}\" header")]
+    MissingHeader,
+    #[error("line {line}: expected 4 fields, found {found}")]
+    FieldCount { line: usize, found: usize },
+    #[error("line {line}: invalid move {value:?}, expected two of 'C' and 'D'")]
+    InvalidMove { line: usize, value: String },
+    #[error("line {line}: invalid score {value:?}")]
+    InvalidScore { line: usize, value: String },
+    #[error("invalid total rounds {0:?}, expected \"total-rounds=<count>\"")]
+    InvalidTotalRounds(String),
+}
+
+// The number of rounds the match was announced to last, if any, is kept so that
+// horizon-aware agents replay the same way.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct History {
+    rounds: Vec<Round>,
+    total_rounds: Option<u32>,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self {
+            rounds: vec![],
+            total_rounds: None,
+        }
+    }
+
+    pub fn total_rounds(&self) -> Option<u32> {
+        self.total_rounds
+    }
+
+    pub fn set_total_rounds(&mut self, rounds: Option<u32>) {
+        self.total_rounds = rounds
+    }
+
+    pub fn push(&mut self, round: Round) {
+        self.rounds.push(round)
+    }
+
+    pub fn rounds(&self) -> &[Round] {
+        &self.rounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.rounds.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rounds.is_empty()
+    }
+
+    pub fn last(&self) -> Option<&Round> {
+        self.rounds.last()
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Round> {
+        self.rounds.iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a History {
+    type Item = &'a Round;
+    type IntoIter = std::slice::Iter<'a, Round>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.rounds.iter()
+    }
+}
+
+fn decision_char(decision: Decision) -> char {
+    match decision {
+        Decision::Cooperated => 'C',
+        Decision::Cheeted => 'D',
+    }
+}
+
+fn parse_decision(value: char) -> Option<Decision> {
+    match value {
+        'C' => Some(Decision::Cooperated),
+        'D' => Some(Decision::Cheeted),
+        _ => None,
+    }
+}
+
+fn parse_move(line: usize, value: &str) -> Result<Move, HistoryError> {
+    let mut chars = value.chars().map(parse_decision);
+
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some(Some(intended)), Some(Some(observed)), None) => Ok(Move::new(intended, observed)),
+        _ => Err(HistoryError::InvalidMove {
+            line,
+            value: value.to_string(),
+        }),
+    }
+}
+
+fn parse_score(line: usize, value: &str) -> Result<i32, HistoryError> {
+    value.parse().map_err(|_| HistoryError::InvalidScore {
+        line,
+        value: value.to_string(),
+    })
+}
+
+// Every round is written as `<left move> <right move> <left score> <right score>`,
+// where a move is the intended decision followed by the observed one, e.g. `CD`.
+// A known match length follows the header as `total-rounds=<count>`.
+impl fmt::Display for History {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.total_rounds {
+            Some(total_rounds) => writeln!(f, "{HEADER} total-rounds={total_rounds}")?,
+            None => writeln!(f, "{HEADER}")?,
+        }
+        for round in &self.rounds {
+            writeln!(
+                f,
+                "{}{} {}{} {} {}",
+                decision_char(round.left.intended),
+                decision_char(round.left.observed),
+                decision_char(round.right.intended),
+                decision_char(round.right.observed),
+                round.left_score,
+                round.right_score,
+            )?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for History {
+    type Err = HistoryError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+
+        let header = lines
+            .next()
+            .and_then(|(_, line)| line.strip_prefix(HEADER))
+            .ok_or(HistoryError::MissingHeader)?;
+        let total_rounds = match header.trim() {
+            "" => None,
+            field if header.starts_with(char::is_whitespace) => {
+                let total_rounds = field
+                    .strip_prefix("total-rounds=")
+                    .and_then(|count| count.parse().ok())
+                    .ok_or_else(|| HistoryError::InvalidTotalRounds(field.to_string()))?;
+                Some(total_rounds)
+            }
+            _ => return Err(HistoryError::MissingHeader),
+        };
+
+        let mut history = History::new();
+        history.set_total_rounds(total_rounds);
+        for (line, content) in lines {
+            let fields = content.split_whitespace().collect::<Vec<_>>();
+            if fields.len() != 4 {
+                return Err(HistoryError::FieldCount {
+                    line,
+                    found: fields.len(),
+                });
+            }
+
+            let left = parse_move(line, fields[0])?;
+            let right = parse_move(line, fields[1])?;
+            history.push(Round {
+                left,
+                right,
+                outcome: RoundOutcome::new(left.observed, right.observed),
+                left_score: parse_score(line, fields[2])?,
+                right_score: parse_score(line, fields[3])?,
+            });
+        }
+
+        Ok(history)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug)]
+pub struct MatchView<'a> {
+    history: &'a History,
+    side: Side,
+    total_rounds: Option<u32>,
+}
+
+impl<'a> MatchView<'a> {
+    pub fn new(history: &'a History, side: Side, total_rounds: Option<u32>) -> Self {
+        Self {
+            history,
+            side,
+            total_rounds,
+        }
+    }
+
+    pub fn side(&self) -> Side {
+        self.side
+    }
+
+    pub fn rounds(&self) -> &'a [Round] {
+        self.history.rounds()
+    }
+
+    pub fn round(&self) -> usize {
+        self.history.len()
+    }
+
+    pub fn total_rounds(&self) -> Option<u32> {
+        self.total_rounds
+    }
+
+    pub fn remaining_rounds(&self) -> Option<u32> {
+        self.total_rounds
+            .map(|total| total.saturating_sub(self.history.len() as u32))
+    }
+
+    pub fn my_moves(&self) -> impl DoubleEndedIterator<Item = Decision> + 'a {
+        let side = self.side;
+        self.history
+            .iter()
+            .map(move |round| round.get(side).observed)
+    }
+
+    pub fn enemy_moves(&self) -> impl DoubleEndedIterator<Item = Decision> + 'a {
+        let side = self.enemy_side();
+        self.history
+            .iter()
+            .map(move |round| round.get(side).observed)
+    }
+
+    pub fn my_last_move(&self) -> Option<Decision> {
+        self.my_moves().next_back()
+    }
+
+    pub fn enemy_last_move(&self) -> Option<Decision> {
+        self.enemy_moves().next_back()
+    }
+
+    pub fn my_score(&self) -> i32 {
+        self.history
+            .last()
+            .map_or(0, |round| round.score(self.side))
+    }
+
+    pub fn enemy_score(&self) -> i32 {
+        self.history
+            .last()
+            .map_or(0, |round| round.score(self.enemy_side()))
+    }
+
+    fn enemy_side(&self) -> Side {
+        match self.side {
+            Side::Left => Side::Right,
+            Side::Right => Side::Left,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ReplayError {
+    #[error("replay needs a fresh game, but {0} rounds were already played")]
+    AlreadyPlayed(usize),
+    #[error("round {round}: {side:?} player played {actual:?}, but {expected:?} was recorded")]
+    Diverged {
+        round: usize,
+        side: Side,
+        expected: Decision,
+        actual: Decision,
+    },
+    #[error("round {round}: scores are {actual:?}, but {expected:?} were recorded")]
+    ScoreMismatch {
+        round: usize,
+        expected: (i32, i32),
+        actual: (i32, i32),
+    },
+}
@@ -0,0 +1,485 @@
+use crate::{rng::Rng, Agent, AgentFactory, Decision, Game, MatchView, PayoffMatrix};
+
+use std::{cell::RefCell, collections::VecDeque, fmt, rc::Rc, str::FromStr, sync::Arc};
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+// A state is the last `memory` rounds (or fewer at the start of a match) seen
+// as (my decision, enemy decision) pairs. States of every history length get
+// their own block of indices, so the opening moves are part of the policy too.
+fn state_count(memory: usize) -> usize {
+    (4usize.pow(memory as u32 + 1) - 1) / 3
+}
+
+#[derive(Clone, Debug)]
+struct RecentRounds {
+    memory: usize,
+    rounds: VecDeque<(Decision, Decision)>,
+}
+
+impl RecentRounds {
+    fn new(memory: usize) -> Self {
+        Self {
+            memory,
+            rounds: VecDeque::with_capacity(memory + 1),
+        }
+    }
+
+    // The rounds as they were observed, so that noise shows up in the state.
+    fn from_history(memory: usize, history: &MatchView<'_>) -> Self {
+        let mut recent = Self::new(memory);
+        let skipped = history.round().saturating_sub(memory);
+        for (mine, enemy) in history.my_moves().zip(history.enemy_moves()).skip(skipped) {
+            recent.push(mine, enemy);
+        }
+        recent
+    }
+
+    fn push(&mut self, mine: Decision, enemy: Decision) {
+        self.rounds.push_back((mine, enemy));
+        if self.rounds.len() > self.memory {
+            self.rounds.pop_front();
+        }
+    }
+
+    fn state(&self) -> usize {
+        let offset = state_count(self.rounds.len()) - 4usize.pow(self.rounds.len() as u32);
+        let bits = self.rounds.iter().fold(0, |bits, (mine, enemy)| {
+            bits * 4
+                + 2 * (*mine == Decision::Cheeted) as usize
+                + (*enemy == Decision::Cheeted) as usize
+        });
+        offset + bits
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum LookupTableError {
+    #[error("expected \"memory-<k> <decisions>\"")]
+    Syntax,
+    #[error("memory {0} is not supported, expected 1 to 8")]
+    Memory(usize),
+    #[error("expected {expected} decisions, found {found}")]
+    Length { expected: usize, found: usize },
+    #[error("invalid decision {0:?}, expected 'C' or 'D'")]
+    Decision(char),
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LookupTable {
+    memory: usize,
+    decisions: Vec<Decision>,
+}
+
+impl LookupTable {
+    pub const MAX_MEMORY: usize = 8;
+
+    pub fn new(memory: usize, decisions: Vec<Decision>) -> Result<Self, LookupTableError> {
+        if memory == 0 || memory > Self::MAX_MEMORY {
+            return Err(LookupTableError::Memory(memory));
+        }
+        if decisions.len() != state_count(memory) {
+            return Err(LookupTableError::Length {
+                expected: state_count(memory),
+                found: decisions.len(),
+            });
+        }
+
+        Ok(Self { memory, decisions })
+    }
+
+    fn random(memory: usize, rng: &mut Rng) -> Self {
+        let decisions = (0..state_count(memory))
+            .map(|_| {
+                if rng.gen_bool(0.5) {
+                    Decision::Cooperated
+                } else {
+                    Decision::Cheeted
+                }
+            })
+            .collect();
+        Self { memory, decisions }
+    }
+
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+
+    pub fn decisions(&self) -> &[Decision] {
+        &self.decisions
+    }
+
+    pub fn agent(self: &Arc<Self>) -> LookupAgent {
+        LookupAgent::new(self.clone())
+    }
+}
+
+impl fmt::Display for LookupTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "memory-{} ", self.memory)?;
+        for decision in &self.decisions {
+            let symbol = match decision {
+                Decision::Cooperated => 'C',
+                Decision::Cheeted => 'D',
+            };
+            write!(f, "{symbol}")?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for LookupTable {
+    type Err = LookupTableError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (memory, decisions) = s
+            .trim()
+            .strip_prefix("memory-")
+            .and_then(|rest| rest.split_once(' '))
+            .ok_or(LookupTableError::Syntax)?;
+        let memory = memory.parse().map_err(|_| LookupTableError::Syntax)?;
+        let decisions = decisions
+            .trim()
+            .chars()
+            .map(|symbol| match symbol {
+                'C' => Ok(Decision::Cooperated),
+                'D' => Ok(Decision::Cheeted),
+                _ => Err(LookupTableError::Decision(symbol)),
+            })
+            .collect::<Result<_, _>>()?;
+
+        Self::new(memory, decisions)
+    }
+}
+
+pub struct LookupAgent {
+    table: Arc<LookupTable>,
+    recent: RecentRounds,
+}
+
+impl LookupAgent {
+    pub fn new(table: Arc<LookupTable>) -> Self {
+        Self {
+            recent: RecentRounds::new(table.memory),
+            table,
+        }
+    }
+}
+
+impl Agent for LookupAgent {
+    fn play(&mut self) -> Decision {
+        self.table.decisions[self.recent.state()]
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        self.recent = RecentRounds::from_history(self.table.memory, history)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct QLearningConfig {
+    pub memory: usize,
+    pub learning_rate: f64,
+    pub discount: f64,
+    pub exploration: f64,
+}
+
+impl Default for QLearningConfig {
+    fn default() -> Self {
+        Self {
+            memory: 2,
+            learning_rate: 0.1,
+            discount: 0.9,
+            exploration: 0.1,
+        }
+    }
+}
+
+type QTable = Vec<[f64; 2]>;
+
+fn action_index(decision: Decision) -> usize {
+    match decision {
+        Decision::Cooperated => 0,
+        Decision::Cheeted => 1,
+    }
+}
+
+fn greedy(values: &[f64; 2]) -> Decision {
+    if values[1] > values[0] {
+        Decision::Cheeted
+    } else {
+        Decision::Cooperated
+    }
+}
+
+pub struct QLearner {
+    config: QLearningConfig,
+    payoffs: PayoffMatrix,
+    table: Rc<RefCell<QTable>>,
+    rng: Rng,
+}
+
+impl QLearner {
+    pub fn new(config: QLearningConfig, seed: u64) -> Self {
+        assert!(
+            config.memory > 0 && config.memory <= LookupTable::MAX_MEMORY,
+            "memory must be within [1, {}]",
+            LookupTable::MAX_MEMORY
+        );
+
+        Self {
+            payoffs: PayoffMatrix::default(),
+            table: Rc::new(RefCell::new(vec![[0.0; 2]; state_count(config.memory)])),
+            config,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    // Every agent shares the learner's table, so whatever it learns is kept.
+    pub fn agent(&mut self) -> QLearningAgent {
+        QLearningAgent {
+            config: self.config,
+            payoffs: self.payoffs,
+            table: self.table.clone(),
+            rng: Rng::new(self.rng.next_u64()),
+            recent: RecentRounds::new(self.config.memory),
+            my_last_decision: Decision::Cooperated,
+        }
+    }
+
+    // Plays `episodes` matches, cycling through the opponents, and returns the
+    // learner's average payoff per round in every episode.
+    pub fn train(&mut self, opponents: &[AgentFactory], episodes: usize, rounds: u32) -> Vec<f64> {
+        assert!(!opponents.is_empty(), "no opponents to train against");
+
+        (0..episodes)
+            .map(|episode| {
+                let opponent = &opponents[episode % opponents.len()];
+                let mut game = Game::with_payoffs(Box::new(self.agent()), opponent(), self.payoffs);
+                game.set_total_rounds(rounds);
+                for _ in 0..rounds {
+                    game.play_round();
+                }
+                game.left_score() as f64 / rounds.max(1) as f64
+            })
+            .collect()
+    }
+
+    pub fn freeze(&self) -> LookupTable {
+        LookupTable {
+            memory: self.config.memory,
+            decisions: self.table.borrow().iter().map(greedy).collect(),
+        }
+    }
+}
+
+pub struct QLearningAgent {
+    config: QLearningConfig,
+    payoffs: PayoffMatrix,
+    table: Rc<RefCell<QTable>>,
+    rng: Rng,
+    recent: RecentRounds,
+    my_last_decision: Decision,
+}
+
+impl Agent for QLearningAgent {
+    fn play(&mut self) -> Decision {
+        self.my_last_decision = if self.rng.gen_bool(self.config.exploration) {
+            if self.rng.gen_bool(0.5) {
+                Decision::Cooperated
+            } else {
+                Decision::Cheeted
+            }
+        } else {
+            greedy(&self.table.borrow()[self.recent.state()])
+        };
+        self.my_last_decision
+    }
+
+    // The previous decision is rewarded with the payoff of the round as it was
+    // observed. That happens once the next round starts, so the last round of a
+    // match teaches nothing.
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        let next = RecentRounds::from_history(self.config.memory, history);
+        if let (Some(mine), Some(enemy)) = (history.my_last_move(), history.enemy_last_move()) {
+            let state = self.recent.state();
+            let next_state = next.state();
+            let reward = self.payoffs.payoffs(mine, enemy).0 as f64;
+
+            let mut table = self.table.borrow_mut();
+            let future = table[next_state][0].max(table[next_state][1]);
+            let value = &mut table[state][action_index(self.my_last_decision)];
+            *value += self.config.learning_rate * (reward + self.config.discount * future - *value);
+        }
+        self.recent = next;
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeneticConfig {
+    pub memory: usize,
+    pub population: usize,
+    pub elite: usize,
+    pub mutation_rate: f64,
+    pub rounds: u32,
+}
+
+impl Default for GeneticConfig {
+    // Axelrod's memory-3 lookup table setup.
+    fn default() -> Self {
+        Self {
+            memory: 3,
+            population: 40,
+            elite: 10,
+            mutation_rate: 0.02,
+            rounds: 64,
+        }
+    }
+}
+
+pub struct GeneticTrainer {
+    config: GeneticConfig,
+    payoffs: PayoffMatrix,
+    population: Vec<Arc<LookupTable>>,
+    fitness: Vec<i32>,
+    rng: Rng,
+}
+
+impl GeneticTrainer {
+    pub fn new(config: GeneticConfig, seed: u64) -> Self {
+        assert!(
+            config.memory > 0 && config.memory <= LookupTable::MAX_MEMORY,
+            "memory must be within [1, {}]",
+            LookupTable::MAX_MEMORY
+        );
+        assert!(
+            config.elite > 0 && config.elite <= config.population,
+            "elite must be within [1, population]"
+        );
+
+        let mut rng = Rng::new(seed);
+        let population = (0..config.population)
+            .map(|_| Arc::new(LookupTable::random(config.memory, &mut rng)))
+            .collect();
+
+        Self {
+            config,
+            payoffs: PayoffMatrix::default(),
+            population,
+            fitness: vec![],
+            rng,
+        }
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    pub fn population(&self) -> &[Arc<LookupTable>] {
+        &self.population
+    }
+
+    // Returns the best fitness of the evaluated generation before breeding the next one.
+    pub fn step(&mut self, opponents: &[AgentFactory]) -> i32 {
+        self.evaluate(opponents);
+
+        let mut ranking = (0..self.population.len()).collect::<Vec<_>>();
+        ranking.sort_by_key(|&index| std::cmp::Reverse(self.fitness[index]));
+        let best_fitness = self.fitness[ranking[0]];
+
+        let elite = ranking[..self.config.elite]
+            .iter()
+            .map(|&index| self.population[index].clone())
+            .collect::<Vec<_>>();
+
+        let mut next = elite.clone();
+        while next.len() < self.config.population {
+            let first = &elite[self.rng.gen_range(elite.len())];
+            let second = &elite[self.rng.gen_range(elite.len())];
+            next.push(Arc::new(self.breed(first, second)));
+        }
+
+        self.population = next;
+        self.fitness.clear();
+        best_fitness
+    }
+
+    pub fn evolve(&mut self, opponents: &[AgentFactory], generations: usize) -> LookupTable {
+        for _ in 0..generations {
+            self.step(opponents);
+        }
+        self.best(opponents)
+    }
+
+    pub fn best(&mut self, opponents: &[AgentFactory]) -> LookupTable {
+        self.evaluate(opponents);
+
+        let best = (0..self.population.len())
+            .max_by_key(|&index| (self.fitness[index], std::cmp::Reverse(index)))
+            .expect("population is not empty");
+        self.population[best].as_ref().clone()
+    }
+
+    fn evaluate(&mut self, opponents: &[AgentFactory]) {
+        if self.fitness.len() == self.population.len() {
+            return;
+        }
+
+        self.fitness = self
+            .population
+            .iter()
+            .map(|table| {
+                opponents
+                    .iter()
+                    .map(|opponent| {
+                        let mut game =
+                            Game::with_payoffs(Box::new(table.agent()), opponent(), self.payoffs);
+                        game.set_total_rounds(self.config.rounds);
+                        for _ in 0..self.config.rounds {
+                            game.play_round();
+                        }
+                        game.left_score()
+                    })
+                    .sum()
+            })
+            .collect();
+    }
+
+    fn breed(&mut self, first: &LookupTable, second: &LookupTable) -> LookupTable {
+        let decisions = first
+            .decisions
+            .iter()
+            .zip(&second.decisions)
+            .map(|(first, second)| {
+                let decision = if self.rng.gen_bool(0.5) {
+                    *first
+                } else {
+                    *second
+                };
+                if self.rng.gen_bool(self.config.mutation_rate) {
+                    decision.flipped()
+                } else {
+                    decision
+                }
+            })
+            .collect();
+
+        LookupTable {
+            memory: self.config.memory,
+            decisions,
+        }
+    }
+}
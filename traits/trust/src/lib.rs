@@ -1,5 +1,49 @@
 #![forbid(unsafe_code)]
 
+mod analysis;
+mod continuation;
+mod evolution;
+mod experiment;
+mod history;
+mod learning;
+mod noise;
+mod parallel;
+mod payoff;
+mod public_goods;
+mod registry;
+mod rng;
+mod script;
+mod spatial;
+mod strategies;
+mod tournament;
+
+pub use analysis::{Estimate, MatchStats, Report, SideStats};
+pub use continuation::{Continuation, MatchLength};
+pub use evolution::{Evolution, Generation, Population};
+pub use experiment::{Experiment, ExperimentError, ExperimentReport, Mode};
+pub use history::{History, HistoryError, MatchView, Move, ReplayError, Round, Side};
+pub use learning::{
+    GeneticConfig, GeneticTrainer, LookupAgent, LookupTable, LookupTableError, QLearner,
+    QLearningAgent, QLearningConfig,
+};
+pub use noise::Noise;
+pub use parallel::{ParallelTournament, SendAgentFactory};
+pub use payoff::{GameFamily, PayoffError, PayoffMatrix};
+pub use public_goods::{GroupAdapter, GroupAgent, GroupRound, PublicGoodsGame};
+pub use registry::{StrategyConstructor, StrategyError, StrategyRegistry};
+pub use script::{
+    FsmAgent, ScriptError, ScriptErrorKind, StateMachine, CHEATING_SCRIPT, COOPERATING_SCRIPT,
+    COPYCAT_SCRIPT, DETECTIVE_SCRIPT, GRUDGER_SCRIPT,
+};
+pub use spatial::{SpatialGame, SpatialStep};
+pub use strategies::{
+    AlternatorAgent, ExtortionAgent, ExtortionError, GenerousTitForTatAgent, HardMajorityAgent,
+    PavlovAgent, ProberAgent, RandomAgent, SoftMajorityAgent, TitForTwoTatsAgent,
+};
+pub use tournament::{
+    AgentFactory, OutcomeCounts, Pairing, Standing, Tournament, TournamentResult,
+};
+
 ////////////////////////////////////////////////////////////////////////////////
 
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
@@ -10,23 +54,75 @@ pub enum RoundOutcome {
     BothCheated,
 }
 
+impl RoundOutcome {
+    pub fn new(left: Decision, right: Decision) -> Self {
+        match (left, right) {
+            (Decision::Cooperated, Decision::Cooperated) => RoundOutcome::BothCooperated,
+            (Decision::Cheeted, Decision::Cooperated) => RoundOutcome::LeftCheated,
+            (Decision::Cooperated, Decision::Cheeted) => RoundOutcome::RightCheated,
+            (Decision::Cheeted, Decision::Cheeted) => RoundOutcome::BothCheated,
+        }
+    }
+}
+
 pub struct Game {
     left_player: Box<dyn Agent>,
     right_player: Box<dyn Agent>,
     left_player_score: i32,
     right_player_score: i32,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    history: History,
+    total_rounds: Option<u32>,
 }
 
 impl Game {
     pub fn new(left: Box<dyn Agent>, right: Box<dyn Agent>) -> Self {
+        Self::with_payoffs(left, right, PayoffMatrix::default())
+    }
+
+    pub fn with_payoffs(
+        left: Box<dyn Agent>,
+        right: Box<dyn Agent>,
+        payoffs: PayoffMatrix,
+    ) -> Self {
         Self {
             left_player: left,
             right_player: right,
             left_player_score: 0,
             right_player_score: 0,
+            payoffs,
+            noise: None,
+            history: History::new(),
+            total_rounds: None,
         }
     }
 
+    pub fn set_total_rounds(&mut self, rounds: u32) {
+        self.total_rounds = Some(rounds);
+        self.history.set_total_rounds(self.total_rounds)
+    }
+
+    pub fn total_rounds(&self) -> Option<u32> {
+        self.total_rounds
+    }
+
+    pub fn set_noise(&mut self, noise: Noise) {
+        self.noise = Some(noise)
+    }
+
+    pub fn noise(&self) -> Option<&Noise> {
+        self.noise.as_ref()
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn payoffs(&self) -> &PayoffMatrix {
+        &self.payoffs
+    }
+
     pub fn left_score(&self) -> i32 {
         self.left_player_score
     }
@@ -36,32 +132,112 @@ impl Game {
     }
 
     pub fn play_round(&mut self) -> RoundOutcome {
-        let left_player_decision = self.left_player.play();
-        let right_player_decision = self.right_player.play();
-
-        self.left_player
-            .accept_enemy_decision(right_player_decision);
-        self.right_player
-            .accept_enemy_decision(left_player_decision);
-
-        match (left_player_decision, right_player_decision) {
-            (Decision::Cooperated, Decision::Cooperated) => {
-                self.left_player_score += 2;
-                self.right_player_score += 2;
-                RoundOutcome::BothCooperated
+        let (left_intended, right_intended) = self.intentions();
+
+        let (left_player_decision, right_player_decision) = match &mut self.noise {
+            Some(noise) => (noise.apply(left_intended), noise.apply(right_intended)),
+            None => (left_intended, right_intended),
+        };
+
+        self.finish_round(
+            Move::new(left_intended, left_player_decision),
+            Move::new(right_intended, right_player_decision),
+        )
+    }
+
+    pub fn play_with_continuation(&mut self, continuation: &mut Continuation) -> MatchLength {
+        let mut realized = 0;
+        loop {
+            self.play_round();
+            realized += 1;
+            if !continuation.proceed(realized) {
+                break;
             }
-            (Decision::Cheeted, Decision::Cooperated) => {
-                self.left_player_score += 3;
-                self.right_player_score -= 1;
-                RoundOutcome::LeftCheated
+        }
+
+        MatchLength {
+            expected: continuation.expected_rounds(),
+            realized,
+        }
+    }
+
+    // A match length recorded in `history` replaces the game's own.
+    pub fn replay(&mut self, history: &History) -> Result<(), ReplayError> {
+        if !self.history.is_empty() {
+            return Err(ReplayError::AlreadyPlayed(self.history.len()));
+        }
+        if let Some(total_rounds) = history.total_rounds() {
+            self.set_total_rounds(total_rounds);
+        }
+
+        for (index, recorded) in history.iter().enumerate() {
+            let round = index + 1;
+
+            let (left_intended, right_intended) = self.intentions();
+            for (side, actual, expected) in [
+                (Side::Left, left_intended, recorded.left.intended),
+                (Side::Right, right_intended, recorded.right.intended),
+            ] {
+                if actual != expected {
+                    return Err(ReplayError::Diverged {
+                        round,
+                        side,
+                        expected,
+                        actual,
+                    });
+                }
             }
-            (Decision::Cooperated, Decision::Cheeted) => {
-                self.left_player_score -= 1;
-                self.right_player_score += 3;
-                RoundOutcome::RightCheated
+
+            // Observed decisions are taken from the record, so noise is reproduced exactly.
+            self.finish_round(recorded.left, recorded.right);
+
+            let actual = (self.left_player_score, self.right_player_score);
+            let expected = (recorded.left_score, recorded.right_score);
+            if actual != expected {
+                return Err(ReplayError::ScoreMismatch {
+                    round,
+                    expected,
+                    actual,
+                });
             }
-            (Decision::Cheeted, Decision::Cheeted) => RoundOutcome::BothCheated,
         }
+
+        Ok(())
+    }
+
+    fn intentions(&mut self) -> (Decision, Decision) {
+        self.left_player.accept_history(&MatchView::new(
+            &self.history,
+            Side::Left,
+            self.total_rounds,
+        ));
+        self.right_player.accept_history(&MatchView::new(
+            &self.history,
+            Side::Right,
+            self.total_rounds,
+        ));
+
+        (self.left_player.play(), self.right_player.play())
+    }
+
+    fn finish_round(&mut self, left: Move, right: Move) -> RoundOutcome {
+        self.left_player.accept_enemy_decision(right.observed);
+        self.right_player.accept_enemy_decision(left.observed);
+
+        let (left_payoff, right_payoff) = self.payoffs.payoffs(left.observed, right.observed);
+        self.left_player_score += left_payoff;
+        self.right_player_score += right_payoff;
+
+        let outcome = RoundOutcome::new(left.observed, right.observed);
+        self.history.push(Round {
+            left,
+            right,
+            outcome,
+            left_score: self.left_player_score,
+            right_score: self.right_player_score,
+        });
+
+        outcome
     }
 }
 
@@ -74,10 +250,21 @@ pub enum Decision {
     Cheeted,
 }
 
+impl Decision {
+    pub fn flipped(self) -> Self {
+        match self {
+            Decision::Cooperated => Decision::Cheeted,
+            Decision::Cheeted => Decision::Cooperated,
+        }
+    }
+}
+
 pub trait Agent {
     fn play(&mut self) -> Decision;
 
     fn accept_enemy_decision(&mut self, _decision: Decision) {}
+
+    fn accept_history(&mut self, _history: &MatchView<'_>) {}
 }
 
 #[derive(Default)]
@@ -212,7 +399,7 @@ impl Agent for DetectiveAgent {
 
     fn accept_enemy_decision(&mut self, decision: Decision) {
         match self.move_number {
-            1 | 2 | 3 | 4 => {
+            1..=4 => {
                 if decision == Decision::Cheeted {
                     self.decive()
                 }
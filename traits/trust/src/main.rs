@@ -0,0 +1,45 @@
+#![forbid(unsafe_code)]
+
+use std::{fs, path::Path, process::ExitCode};
+use trust::{Experiment, ExperimentError};
+
+fn run(path: &Path) -> Result<(), ExperimentError> {
+    let source = fs::read_to_string(path).map_err(|error| ExperimentError::Io {
+        path: path.to_path_buf(),
+        error,
+    })?;
+    let experiment = source.parse::<Experiment>()?;
+
+    let base = path.parent().unwrap_or(Path::new("."));
+    let registry = experiment.registry(base)?;
+    let report = experiment.run(&registry)?;
+    print!("{}", report.summary);
+
+    if let Some(output) = experiment.output_path(base) {
+        fs::write(&output, &report.data).map_err(|error| ExperimentError::Io {
+            path: output.clone(),
+            error,
+        })?;
+        println!("results written to {}", output.display());
+    }
+
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args = std::env::args().collect::<Vec<String>>();
+
+    if args.len() != 2 {
+        eprintln!("usage: {} <experiment file>", args[0]);
+        eprintln!("keys: {}", Experiment::keys().join(", "));
+        return ExitCode::from(2);
+    }
+
+    match run(Path::new(&args[1])) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(error) => {
+            eprintln!("error: {error}");
+            ExitCode::FAILURE
+        }
+    }
+}
@@ -0,0 +1,49 @@
+use crate::{
+    rng::{self, Rng},
+    Decision,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug)]
+pub struct Noise {
+    probability: f64,
+    seed: u64,
+    rng: Rng,
+}
+
+impl Noise {
+    pub fn new(probability: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&probability),
+            "noise probability must be within [0, 1]"
+        );
+
+        Self {
+            probability,
+            seed,
+            rng: Rng::new(seed),
+        }
+    }
+
+    pub fn probability(&self) -> f64 {
+        self.probability
+    }
+
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    pub fn fork(&self, stream: u64) -> Self {
+        let seed = rng::fork(self.seed, stream);
+        Self::new(self.probability, seed)
+    }
+
+    pub fn apply(&mut self, decision: Decision) -> Decision {
+        if self.rng.gen_bool(self.probability) {
+            decision.flipped()
+        } else {
+            decision
+        }
+    }
+}
@@ -0,0 +1,128 @@
+use crate::{
+    tournament::{pairing_stream, play_pairing},
+    Agent, Continuation, Noise, PayoffMatrix, TournamentResult,
+};
+
+use std::{
+    num::NonZeroUsize,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Arc, Mutex,
+    },
+    thread,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub type SendAgentFactory = Arc<dyn Fn() -> Box<dyn Agent + Send> + Send + Sync>;
+
+pub struct ParallelTournament {
+    roster: Vec<(String, SendAgentFactory)>,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    continuation: Option<Continuation>,
+    threads: usize,
+}
+
+impl ParallelTournament {
+    pub fn new(rounds: u32) -> Self {
+        Self {
+            roster: vec![],
+            rounds,
+            payoffs: PayoffMatrix::default(),
+            noise: None,
+            continuation: None,
+            threads: thread::available_parallelism().map_or(1, NonZeroUsize::get),
+        }
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    pub fn set_noise(&mut self, noise: Noise) -> &mut Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    pub fn set_continuation(&mut self, continuation: Continuation) -> &mut Self {
+        self.continuation = Some(continuation);
+        self
+    }
+
+    pub fn set_threads(&mut self, threads: usize) -> &mut Self {
+        assert!(threads > 0, "at least one thread is required");
+        self.threads = threads;
+        self
+    }
+
+    pub fn add_agent<S, F>(&mut self, name: S, factory: F) -> &mut Self
+    where
+        S: Into<String>,
+        F: Fn() -> Box<dyn Agent + Send> + Send + Sync + 'static,
+    {
+        self.roster.push((name.into(), Arc::new(factory)));
+        self
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    pub fn len(&self) -> usize {
+        self.roster.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roster.is_empty()
+    }
+
+    // Pairings are handed out through a shared counter and merged by index, so the
+    // result is identical to a sequential `Tournament` regardless of scheduling.
+    pub fn play(&self) -> TournamentResult {
+        let players = self.roster.len();
+        let pairings = (0..players)
+            .flat_map(|left| (left + 1..players).map(move |right| (left, right)))
+            .collect::<Vec<_>>();
+
+        let next = AtomicUsize::new(0);
+        let results = Mutex::new(vec![None; pairings.len()]);
+
+        thread::scope(|scope| {
+            for _ in 0..self.threads.min(pairings.len()) {
+                scope.spawn(|| loop {
+                    let index = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(&(left, right)) = pairings.get(index) else {
+                        break;
+                    };
+
+                    let stream = pairing_stream(players, left, right);
+                    let pairing = play_pairing(
+                        (self.roster[left].1)(),
+                        (self.roster[right].1)(),
+                        self.rounds,
+                        self.payoffs,
+                        self.noise.as_ref().map(|noise| noise.fork(stream)),
+                        self.continuation
+                            .as_ref()
+                            .map(|continuation| continuation.fork(stream)),
+                    );
+                    results.lock().unwrap()[index] = Some(pairing);
+                });
+            }
+        });
+
+        let mut result = TournamentResult::new(self.roster.iter().map(|(name, _)| name.clone()));
+        for ((left, right), pairing) in pairings.into_iter().zip(results.into_inner().unwrap()) {
+            result.record(left, right, pairing.expect("every pairing is played"));
+        }
+
+        result
+    }
+}
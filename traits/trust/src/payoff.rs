@@ -0,0 +1,159 @@
+use crate::Decision;
+
+use std::fmt;
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum GameFamily {
+    PrisonersDilemma,
+    StagHunt,
+    Chicken,
+    Snowdrift,
+}
+
+impl GameFamily {
+    fn ordering(&self) -> &'static str {
+        match self {
+            GameFamily::PrisonersDilemma => "T > R > P > S",
+            GameFamily::StagHunt => "R > T >= P > S",
+            GameFamily::Chicken | GameFamily::Snowdrift => "T > R > S > P",
+        }
+    }
+}
+
+impl fmt::Display for GameFamily {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GameFamily::PrisonersDilemma => "prisoner's dilemma",
+            GameFamily::StagHunt => "stag hunt",
+            GameFamily::Chicken => "chicken",
+            GameFamily::Snowdrift => "snowdrift",
+        };
+        f.write_str(name)
+    }
+}
+
+#[derive(Error, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PayoffError {
+    #[error("payoffs of {family} must satisfy {}", .family.ordering())]
+    Ordering { family: GameFamily },
+    #[error("payoffs of prisoner's dilemma must satisfy 2R > T + S")]
+    AlternationPays,
+    #[error("snowdrift benefit must exceed the cost and the cost must be positive")]
+    Snowdrift,
+    #[error("payoffs do not fit in 32 bits")]
+    Overflow,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PayoffMatrix {
+    family: GameFamily,
+    temptation: i32,
+    reward: i32,
+    punishment: i32,
+    sucker: i32,
+}
+
+impl Default for PayoffMatrix {
+    fn default() -> Self {
+        Self {
+            family: GameFamily::PrisonersDilemma,
+            temptation: 3,
+            reward: 2,
+            punishment: 0,
+            sucker: -1,
+        }
+    }
+}
+
+impl PayoffMatrix {
+    pub fn new(
+        family: GameFamily,
+        temptation: i32,
+        reward: i32,
+        punishment: i32,
+        sucker: i32,
+    ) -> Result<Self, PayoffError> {
+        let matrix = Self {
+            family,
+            temptation,
+            reward,
+            punishment,
+            sucker,
+        };
+        matrix.validate()?;
+        Ok(matrix)
+    }
+
+    pub fn snowdrift(benefit: i32, cost: i32) -> Result<Self, PayoffError> {
+        if cost <= 0 || benefit <= cost {
+            return Err(PayoffError::Snowdrift);
+        }
+
+        // Payoffs are doubled so that the shared cost c / 2 stays integral.
+        let temptation = benefit.checked_mul(2).ok_or(PayoffError::Overflow)?;
+        Self::new(
+            GameFamily::Snowdrift,
+            temptation,
+            temptation - cost,
+            0,
+            2 * (benefit - cost),
+        )
+    }
+
+    pub fn validate(&self) -> Result<(), PayoffError> {
+        let (t, r, p, s) = (self.temptation, self.reward, self.punishment, self.sucker);
+
+        let is_ordered = match self.family {
+            GameFamily::PrisonersDilemma => t > r && r > p && p > s,
+            GameFamily::StagHunt => r > t && t >= p && p > s,
+            GameFamily::Chicken | GameFamily::Snowdrift => t > r && r > s && s > p,
+        };
+        if !is_ordered {
+            return Err(PayoffError::Ordering {
+                family: self.family,
+            });
+        }
+
+        // Widened so that large payoffs cannot overflow.
+        let (t, r, s) = (i64::from(t), i64::from(r), i64::from(s));
+        if self.family == GameFamily::PrisonersDilemma && 2 * r <= t + s {
+            return Err(PayoffError::AlternationPays);
+        }
+
+        Ok(())
+    }
+
+    pub fn family(&self) -> GameFamily {
+        self.family
+    }
+
+    pub fn temptation(&self) -> i32 {
+        self.temptation
+    }
+
+    pub fn reward(&self) -> i32 {
+        self.reward
+    }
+
+    pub fn punishment(&self) -> i32 {
+        self.punishment
+    }
+
+    pub fn sucker(&self) -> i32 {
+        self.sucker
+    }
+
+    pub fn payoffs(&self, left: Decision, right: Decision) -> (i32, i32) {
+        match (left, right) {
+            (Decision::Cooperated, Decision::Cooperated) => (self.reward, self.reward),
+            (Decision::Cheeted, Decision::Cooperated) => (self.temptation, self.sucker),
+            (Decision::Cooperated, Decision::Cheeted) => (self.sucker, self.temptation),
+            (Decision::Cheeted, Decision::Cheeted) => (self.punishment, self.punishment),
+        }
+    }
+}
@@ -0,0 +1,200 @@
+use crate::{Agent, Decision, History, MatchView, Move, PayoffMatrix, Round, RoundOutcome, Side};
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub trait GroupAgent {
+    fn play(&mut self) -> Decision;
+
+    fn accept_group_decisions(&mut self, _others: &[Decision]) {}
+}
+
+// Lets any two-player agent join a group: the wrapped agent sees the group as
+// a single enemy who cooperated if enough of the other players did. That match
+// is recorded as a two-player history, scored with the default payoffs, so that
+// history-driven strategies play in groups as they do in pairs.
+pub struct GroupAdapter {
+    agent: Box<dyn Agent>,
+    threshold: f64,
+    history: History,
+    my_last_decision: Decision,
+}
+
+impl GroupAdapter {
+    pub fn new(agent: Box<dyn Agent>) -> Self {
+        Self::with_threshold(agent, 0.5)
+    }
+
+    pub fn with_threshold(agent: Box<dyn Agent>, threshold: f64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&threshold),
+            "threshold must be within [0, 1]"
+        );
+
+        Self {
+            agent,
+            threshold,
+            history: History::new(),
+            my_last_decision: Decision::Cooperated,
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+}
+
+impl GroupAgent for GroupAdapter {
+    fn play(&mut self) -> Decision {
+        self.agent
+            .accept_history(&MatchView::new(&self.history, Side::Left, None));
+        self.my_last_decision = self.agent.play();
+        self.my_last_decision
+    }
+
+    fn accept_group_decisions(&mut self, others: &[Decision]) {
+        let cooperators = others
+            .iter()
+            .filter(|decision| **decision == Decision::Cooperated)
+            .count();
+
+        let decision = if cooperators as f64 >= self.threshold * others.len() as f64 {
+            Decision::Cooperated
+        } else {
+            Decision::Cheeted
+        };
+        self.agent.accept_enemy_decision(decision);
+
+        let mine = self.my_last_decision;
+        let (my_payoff, group_payoff) = PayoffMatrix::default().payoffs(mine, decision);
+        let (my_score, group_score) = self
+            .history
+            .last()
+            .map_or((0, 0), |round| (round.left_score, round.right_score));
+        self.history.push(Round {
+            left: Move::new(mine, mine),
+            right: Move::new(decision, decision),
+            outcome: RoundOutcome::new(mine, decision),
+            left_score: my_score + my_payoff,
+            right_score: group_score + group_payoff,
+        });
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct GroupRound {
+    pub decisions: Vec<Decision>,
+    pub payoffs: Vec<f64>,
+}
+
+impl GroupRound {
+    pub fn contributors(&self) -> usize {
+        self.decisions
+            .iter()
+            .filter(|decision| **decision == Decision::Cooperated)
+            .count()
+    }
+}
+
+pub struct PublicGoodsGame {
+    players: Vec<Box<dyn GroupAgent>>,
+    scores: Vec<f64>,
+    contribution: f64,
+    multiplier: f64,
+    history: Vec<GroupRound>,
+}
+
+impl PublicGoodsGame {
+    pub fn new(players: Vec<Box<dyn GroupAgent>>, multiplier: f64) -> Self {
+        Self::with_contribution(players, multiplier, 1.0)
+    }
+
+    pub fn with_contribution(
+        players: Vec<Box<dyn GroupAgent>>,
+        multiplier: f64,
+        contribution: f64,
+    ) -> Self {
+        assert!(
+            players.len() >= 2,
+            "public goods game needs at least two players"
+        );
+        assert!(multiplier > 0.0, "multiplier must be positive");
+        assert!(contribution > 0.0, "contribution must be positive");
+
+        Self {
+            scores: vec![0.0; players.len()],
+            players,
+            contribution,
+            multiplier,
+            history: vec![],
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.players.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.players.is_empty()
+    }
+
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    pub fn contribution(&self) -> f64 {
+        self.contribution
+    }
+
+    pub fn score(&self, player: usize) -> f64 {
+        self.scores[player]
+    }
+
+    pub fn scores(&self) -> &[f64] {
+        &self.scores
+    }
+
+    pub fn history(&self) -> &[GroupRound] {
+        &self.history
+    }
+
+    pub fn play_round(&mut self) -> &GroupRound {
+        let decisions = self
+            .players
+            .iter_mut()
+            .map(|player| player.play())
+            .collect::<Vec<_>>();
+
+        for (index, player) in self.players.iter_mut().enumerate() {
+            let others = decisions
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != index)
+                .map(|(_, decision)| *decision)
+                .collect::<Vec<_>>();
+            player.accept_group_decisions(&others);
+        }
+
+        let contributors = decisions
+            .iter()
+            .filter(|decision| **decision == Decision::Cooperated)
+            .count();
+        let share =
+            self.contribution * contributors as f64 * self.multiplier / self.players.len() as f64;
+
+        let payoffs = decisions
+            .iter()
+            .map(|decision| match decision {
+                Decision::Cooperated => share - self.contribution,
+                Decision::Cheeted => share,
+            })
+            .collect::<Vec<_>>();
+        for (score, payoff) in self.scores.iter_mut().zip(&payoffs) {
+            *score += payoff;
+        }
+
+        self.history.push(GroupRound { decisions, payoffs });
+        self.history.last().unwrap()
+    }
+}
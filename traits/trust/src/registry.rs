@@ -0,0 +1,230 @@
+use crate::{
+    Agent, AlternatorAgent, CheatingAgent, CooperatingAgent, CopycatAgent, DetectiveAgent,
+    ExtortionAgent, ExtortionError, GenerousTitForTatAgent, GrudgerAgent, HardMajorityAgent,
+    PavlovAgent, PayoffMatrix, ProberAgent, RandomAgent, ScriptError, SoftMajorityAgent,
+    StateMachine, TitForTwoTatsAgent,
+};
+
+use std::sync::Arc;
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Clone, Debug, PartialEq)]
+pub enum StrategyError {
+    #[error("unknown strategy {0:?}")]
+    Unknown(String),
+    #[error("malformed strategy {0:?}, expected \"name\" or \"name(parameter)\"")]
+    Malformed(String),
+    #[error("strategy {0:?} does not take a parameter")]
+    UnexpectedParameter(String),
+    #[error("invalid parameter {value:?} for strategy {name:?}")]
+    InvalidParameter { name: String, value: String },
+    #[error(transparent)]
+    Extortion(#[from] ExtortionError),
+}
+
+pub type StrategyConstructor =
+    Arc<dyn Fn(Option<f64>, u64) -> Result<Box<dyn Agent + Send>, StrategyError> + Send + Sync>;
+
+pub struct StrategyRegistry {
+    strategies: Vec<(String, StrategyConstructor)>,
+}
+
+impl Default for StrategyRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn simple<A, F>(name: &'static str, constructor: F) -> StrategyConstructor
+where
+    A: Agent + Send + 'static,
+    F: Fn() -> A + Send + Sync + 'static,
+{
+    Arc::new(move |parameter, _seed| match parameter {
+        Some(_) => Err(StrategyError::UnexpectedParameter(name.to_string())),
+        None => Ok(Box::new(constructor()) as Box<dyn Agent + Send>),
+    })
+}
+
+fn probability(
+    name: &'static str,
+    parameter: Option<f64>,
+    default: f64,
+) -> Result<f64, StrategyError> {
+    let probability = parameter.unwrap_or(default);
+    if !(0.0..=1.0).contains(&probability) {
+        return Err(StrategyError::InvalidParameter {
+            name: name.to_string(),
+            value: probability.to_string(),
+        });
+    }
+    Ok(probability)
+}
+
+impl StrategyRegistry {
+    pub fn empty() -> Self {
+        Self { strategies: vec![] }
+    }
+
+    pub fn new() -> Self {
+        Self::with_payoffs(PayoffMatrix::default())
+    }
+
+    // Strategies that depend on the payoffs, like extortion, are solved for `payoffs`.
+    pub fn with_payoffs(payoffs: PayoffMatrix) -> Self {
+        let mut registry = Self::empty();
+
+        registry
+            .register("cheating", simple("cheating", CheatingAgent::default))
+            .register(
+                "cooperating",
+                simple("cooperating", CooperatingAgent::default),
+            )
+            .register("grudger", simple("grudger", GrudgerAgent::new))
+            .register("copycat", simple("copycat", CopycatAgent::new))
+            .register("detective", simple("detective", DetectiveAgent::new))
+            .register(
+                "tit-for-two-tats",
+                simple("tit-for-two-tats", TitForTwoTatsAgent::new),
+            )
+            .register(
+                "generous-tit-for-tat",
+                Arc::new(|parameter, seed| {
+                    let generosity = probability("generous-tit-for-tat", parameter, 0.1)?;
+                    Ok(Box::new(GenerousTitForTatAgent::new(generosity, seed)))
+                }),
+            )
+            .register("pavlov", simple("pavlov", PavlovAgent::new))
+            .register(
+                "random",
+                Arc::new(|parameter, seed| {
+                    let cooperation_probability = probability("random", parameter, 0.5)?;
+                    Ok(Box::new(RandomAgent::new(cooperation_probability, seed)))
+                }),
+            )
+            .register("alternator", simple("alternator", AlternatorAgent::new))
+            .register(
+                "soft-majority",
+                simple("soft-majority", SoftMajorityAgent::new),
+            )
+            .register(
+                "hard-majority",
+                simple("hard-majority", HardMajorityAgent::new),
+            )
+            .register("prober", simple("prober", ProberAgent::new))
+            .register(
+                "extortion",
+                Arc::new(move |parameter, seed| {
+                    let factor = parameter.unwrap_or(2.0);
+                    Ok(Box::new(ExtortionAgent::new(payoffs, factor, seed)?))
+                }),
+            );
+
+        registry
+    }
+
+    pub fn register<S: Into<String>>(
+        &mut self,
+        name: S,
+        constructor: StrategyConstructor,
+    ) -> &mut Self {
+        let name = name.into();
+        match self.strategies.iter_mut().find(|(known, _)| *known == name) {
+            Some(entry) => entry.1 = constructor,
+            None => self.strategies.push((name, constructor)),
+        }
+        self
+    }
+
+    pub fn register_script<S: Into<String>>(
+        &mut self,
+        name: S,
+        source: &str,
+    ) -> Result<&mut Self, ScriptError> {
+        let machine = Arc::new(StateMachine::parse(source)?);
+        let name = name.into();
+        let strategy = name.clone();
+
+        Ok(self.register(
+            name,
+            Arc::new(move |parameter, _seed| match parameter {
+                Some(_) => Err(StrategyError::UnexpectedParameter(strategy.clone())),
+                None => Ok(Box::new(machine.agent()) as Box<dyn Agent + Send>),
+            }),
+        ))
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.strategies.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn contains(&self, name: &str) -> bool {
+        self.strategies.iter().any(|(known, _)| known == name)
+    }
+
+    pub fn create(&self, spec: &str, seed: u64) -> Result<Box<dyn Agent>, StrategyError> {
+        Ok(self.create_send(spec, seed)?)
+    }
+
+    pub fn create_send(
+        &self,
+        spec: &str,
+        seed: u64,
+    ) -> Result<Box<dyn Agent + Send>, StrategyError> {
+        let (constructor, parameter) = self.resolve(spec)?;
+        constructor(parameter, seed)
+    }
+
+    pub fn factory(
+        &self,
+        spec: &str,
+        seed: u64,
+    ) -> Result<impl Fn() -> Box<dyn Agent> + Send + Sync + 'static, StrategyError> {
+        let (constructor, parameter) = self.resolve(spec)?;
+        constructor(parameter, seed)?;
+
+        Ok(move || -> Box<dyn Agent> {
+            constructor(parameter, seed).expect("strategy was validated on creation")
+        })
+    }
+
+    pub fn send_factory(
+        &self,
+        spec: &str,
+        seed: u64,
+    ) -> Result<impl Fn() -> Box<dyn Agent + Send> + Send + Sync + 'static, StrategyError> {
+        let (constructor, parameter) = self.resolve(spec)?;
+        constructor(parameter, seed)?;
+
+        Ok(move || constructor(parameter, seed).expect("strategy was validated on creation"))
+    }
+
+    fn resolve(&self, spec: &str) -> Result<(StrategyConstructor, Option<f64>), StrategyError> {
+        let spec = spec.trim();
+        let (name, parameter) = match spec.split_once('(') {
+            None => (spec, None),
+            Some((name, rest)) => {
+                let value = rest
+                    .strip_suffix(')')
+                    .ok_or_else(|| StrategyError::Malformed(spec.to_string()))?
+                    .trim();
+                let parameter =
+                    value
+                        .parse::<f64>()
+                        .map_err(|_| StrategyError::InvalidParameter {
+                            name: name.trim().to_string(),
+                            value: value.to_string(),
+                        })?;
+                (name.trim(), Some(parameter))
+            }
+        };
+
+        self.strategies
+            .iter()
+            .find(|(known, _)| known == name)
+            .map(|(_, constructor)| (constructor.clone(), parameter))
+            .ok_or_else(|| StrategyError::Unknown(name.to_string()))
+    }
+}
@@ -0,0 +1,288 @@
+use crate::{Agent, Decision};
+
+use std::{collections::HashMap, str::FromStr, sync::Arc};
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub const CHEATING_SCRIPT: &str = "\
+state cheat cheat
+";
+
+pub const COOPERATING_SCRIPT: &str = "\
+state cooperate cooperate
+";
+
+pub const COPYCAT_SCRIPT: &str = "\
+start nice
+state nice cooperate
+state mean cheat
+on nice cheat -> mean
+on mean cooperate -> nice
+";
+
+pub const GRUDGER_SCRIPT: &str = "\
+start trusting
+state trusting cooperate
+state grudging cheat
+on trusting cheat -> grudging
+";
+
+pub const DETECTIVE_SCRIPT: &str = "\
+# Opens with cooperate, cheat, cooperate, cooperate. If the enemy ever cheated
+# during the opening it plays copycat afterwards, otherwise it always cheats.
+start first
+state first cooperate
+state second cheat
+state third cooperate
+state fourth cooperate
+state second_deceived cheat
+state third_deceived cooperate
+state fourth_deceived cooperate
+state exploit cheat
+state nice cooperate
+state mean cheat
+on first cooperate -> second
+on first cheat -> second_deceived
+on second cooperate -> third
+on second cheat -> third_deceived
+on third cooperate -> fourth
+on third cheat -> fourth_deceived
+on fourth cooperate -> exploit
+on fourth cheat -> mean
+on second_deceived any -> third_deceived
+on third_deceived any -> fourth_deceived
+on fourth_deceived cooperate -> nice
+on fourth_deceived cheat -> mean
+on nice cheat -> mean
+on mean cooperate -> nice
+";
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub enum ScriptErrorKind {
+    #[error("unknown statement {0:?}, expected \"start\", \"state\" or \"on\"")]
+    UnknownStatement(String),
+    #[error("expected {expected}")]
+    Syntax { expected: &'static str },
+    #[error("invalid decision {0:?}, expected \"cooperate\" or \"cheat\"")]
+    InvalidDecision(String),
+    #[error("state {0:?} is declared twice")]
+    DuplicateState(String),
+    #[error("state {0:?} is not declared")]
+    UnknownState(String),
+    #[error("transition from {state:?} on {:?} is declared twice", decision_keyword(*.decision))]
+    DuplicateTransition { state: String, decision: Decision },
+    #[error("start state is declared twice")]
+    DuplicateStart,
+    #[error("script declares no states")]
+    NoStates,
+}
+
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+#[error("line {line}: {kind}")]
+pub struct ScriptError {
+    pub line: usize,
+    pub kind: ScriptErrorKind,
+}
+
+impl ScriptError {
+    fn new(line: usize, kind: ScriptErrorKind) -> Self {
+        Self { line, kind }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct StateMachine {
+    names: Vec<String>,
+    outputs: Vec<Decision>,
+    // For every state: the next state after the enemy cooperated and after it cheated.
+    transitions: Vec<[usize; 2]>,
+    start: usize,
+}
+
+fn transition_index(decision: Decision) -> usize {
+    match decision {
+        Decision::Cooperated => 0,
+        Decision::Cheeted => 1,
+    }
+}
+
+fn decision_keyword(decision: Decision) -> &'static str {
+    match decision {
+        Decision::Cooperated => "cooperate",
+        Decision::Cheeted => "cheat",
+    }
+}
+
+fn parse_decision(line: usize, value: &str) -> Result<Decision, ScriptError> {
+    match value {
+        "cooperate" => Ok(Decision::Cooperated),
+        "cheat" => Ok(Decision::Cheeted),
+        _ => Err(ScriptError::new(
+            line,
+            ScriptErrorKind::InvalidDecision(value.to_string()),
+        )),
+    }
+}
+
+fn syntax_error(line: usize, expected: &'static str) -> ScriptError {
+    ScriptError::new(line, ScriptErrorKind::Syntax { expected })
+}
+
+impl StateMachine {
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let statements = source
+            .lines()
+            .enumerate()
+            .map(|(index, line)| {
+                let content = line.split('#').next().unwrap_or_default();
+                (index + 1, content.split_whitespace().collect::<Vec<_>>())
+            })
+            .filter(|(_, tokens)| !tokens.is_empty())
+            .collect::<Vec<_>>();
+
+        // States are collected first so that transitions may refer to states declared later.
+        let mut states = HashMap::new();
+        let mut names = vec![];
+        let mut outputs = vec![];
+        for (line, tokens) in &statements {
+            if tokens[0] != "state" {
+                continue;
+            }
+            let [_, name, output] = tokens[..] else {
+                return Err(syntax_error(*line, "\"state <name> <decision>\""));
+            };
+            if states.insert(name, names.len()).is_some() {
+                return Err(ScriptError::new(
+                    *line,
+                    ScriptErrorKind::DuplicateState(name.to_string()),
+                ));
+            }
+            names.push(name.to_string());
+            outputs.push(parse_decision(*line, output)?);
+        }
+
+        let resolve = |line: usize, name: &str| {
+            states.get(name).copied().ok_or_else(|| {
+                ScriptError::new(line, ScriptErrorKind::UnknownState(name.to_string()))
+            })
+        };
+
+        let mut start = None;
+        let mut transitions = (0..names.len())
+            .map(|state| [state, state])
+            .collect::<Vec<_>>();
+        let mut declared = vec![[false; 2]; names.len()];
+        for (line, tokens) in &statements {
+            match tokens[0] {
+                "state" => {}
+                "start" => {
+                    let [_, name] = tokens[..] else {
+                        return Err(syntax_error(*line, "\"start <state>\""));
+                    };
+                    if start.replace(resolve(*line, name)?).is_some() {
+                        return Err(ScriptError::new(*line, ScriptErrorKind::DuplicateStart));
+                    }
+                }
+                "on" => {
+                    let [_, from, decision, "->", to] = tokens[..] else {
+                        return Err(syntax_error(*line, "\"on <state> <decision> -> <state>\""));
+                    };
+                    let from = resolve(*line, from)?;
+                    let to = resolve(*line, to)?;
+                    let decisions = match decision {
+                        "any" => vec![Decision::Cooperated, Decision::Cheeted],
+                        _ => vec![parse_decision(*line, decision)?],
+                    };
+
+                    for decision in decisions {
+                        let index = transition_index(decision);
+                        if declared[from][index] {
+                            return Err(ScriptError::new(
+                                *line,
+                                ScriptErrorKind::DuplicateTransition {
+                                    state: names[from].clone(),
+                                    decision,
+                                },
+                            ));
+                        }
+                        declared[from][index] = true;
+                        transitions[from][index] = to;
+                    }
+                }
+                statement => {
+                    return Err(ScriptError::new(
+                        *line,
+                        ScriptErrorKind::UnknownStatement(statement.to_string()),
+                    ))
+                }
+            }
+        }
+
+        if names.is_empty() {
+            let line = statements.last().map_or(1, |(line, _)| *line);
+            return Err(ScriptError::new(line, ScriptErrorKind::NoStates));
+        }
+
+        Ok(Self {
+            names,
+            outputs,
+            transitions,
+            start: start.unwrap_or(0),
+        })
+    }
+
+    pub fn states(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| name.as_str())
+    }
+
+    pub fn start(&self) -> &str {
+        &self.names[self.start]
+    }
+
+    pub fn agent(self: &Arc<Self>) -> FsmAgent {
+        FsmAgent::new(self.clone())
+    }
+}
+
+impl FromStr for StateMachine {
+    type Err = ScriptError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::parse(s)
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct FsmAgent {
+    machine: Arc<StateMachine>,
+    state: usize,
+}
+
+impl FsmAgent {
+    pub fn new(machine: Arc<StateMachine>) -> Self {
+        Self {
+            state: machine.start,
+            machine,
+        }
+    }
+
+    pub fn state(&self) -> &str {
+        &self.machine.names[self.state]
+    }
+}
+
+impl Agent for FsmAgent {
+    fn play(&mut self) -> Decision {
+        self.machine.outputs[self.state]
+    }
+
+    fn accept_enemy_decision(&mut self, decision: Decision) {
+        self.state = self.machine.transitions[self.state][transition_index(decision)];
+    }
+}
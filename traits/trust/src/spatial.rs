@@ -0,0 +1,189 @@
+use crate::{rng::Rng, Agent, AgentFactory, Game, Noise, PayoffMatrix};
+
+use conway::Grid;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone)]
+pub struct SpatialStep {
+    pub number: usize,
+    pub map: Grid<usize>,
+    pub scores: Grid<i32>,
+    pub composition: Vec<(String, usize)>,
+}
+
+pub struct SpatialGame {
+    species: Vec<(String, AgentFactory)>,
+    map: Grid<usize>,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    step: usize,
+}
+
+impl SpatialGame {
+    pub fn new(rows: usize, cols: usize, rounds: u32) -> Self {
+        assert!(rows > 0 && cols > 0, "grid must not be empty");
+
+        Self {
+            species: vec![],
+            map: Grid::new(rows, cols),
+            rounds,
+            payoffs: PayoffMatrix::default(),
+            noise: None,
+            step: 0,
+        }
+    }
+
+    pub fn add_species<S, F>(&mut self, name: S, factory: F) -> usize
+    where
+        S: Into<String>,
+        F: Fn() -> Box<dyn Agent> + 'static,
+    {
+        self.species.push((name.into(), Box::new(factory)));
+        self.species.len() - 1
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    pub fn set_noise(&mut self, noise: Noise) -> &mut Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    pub fn set_cell(&mut self, species: usize, row: usize, col: usize) {
+        assert!(species < self.species.len(), "unknown species {species}");
+        self.map.set(species, row, col)
+    }
+
+    pub fn fill_random(&mut self, seed: u64) {
+        assert!(!self.species.is_empty(), "no species to fill the grid with");
+
+        let mut rng = Rng::new(seed);
+        let (rows, cols) = self.map.size();
+        for row in 0..rows {
+            for col in 0..cols {
+                self.map.set(rng.gen_range(self.species.len()), row, col);
+            }
+        }
+    }
+
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.species.iter().map(|(name, _)| name.as_str())
+    }
+
+    pub fn map(&self) -> &Grid<usize> {
+        &self.map
+    }
+
+    pub fn composition(&self) -> Vec<(String, usize)> {
+        if self.species.is_empty() {
+            return vec![];
+        }
+
+        let mut counts = vec![0; self.species.len()];
+        let (rows, cols) = self.map.size();
+        for row in 0..rows {
+            for col in 0..cols {
+                counts[*self.map.get(row, col)] += 1;
+            }
+        }
+
+        self.species
+            .iter()
+            .zip(counts)
+            .map(|((name, _), count)| (name.clone(), count))
+            .collect()
+    }
+
+    pub fn run(&mut self, steps: usize) -> Vec<SpatialStep> {
+        (0..steps).map(|_| self.step()).collect()
+    }
+
+    // The grid starts filled with species 0, so at least one has to be registered.
+    pub fn step(&mut self) -> SpatialStep {
+        assert!(
+            !self.species.is_empty(),
+            "add a species before stepping the game"
+        );
+
+        let scores = self.play_neighbours();
+        let (rows, cols) = self.map.size();
+
+        let mut map = self.map.clone();
+        for row in 0..rows {
+            for col in 0..cols {
+                let mut best = (row, col);
+                for (neighbour_row, neighbour_col) in self.map.neighbours(row, col) {
+                    if scores.get(neighbour_row, neighbour_col) > scores.get(best.0, best.1) {
+                        best = (neighbour_row, neighbour_col);
+                    }
+                }
+                map.set(*self.map.get(best.0, best.1), row, col);
+            }
+        }
+
+        self.map = map;
+        self.step += 1;
+
+        SpatialStep {
+            number: self.step,
+            map: self.map.clone(),
+            scores,
+            composition: self.composition(),
+        }
+    }
+
+    fn play_neighbours(&self) -> Grid<i32> {
+        let (rows, cols) = self.map.size();
+        let mut scores = Grid::new(rows, cols);
+
+        for row in 0..rows {
+            for col in 0..cols {
+                let cell = row * cols + col;
+                for (neighbour_row, neighbour_col) in self.map.neighbours(row, col) {
+                    let neighbour = neighbour_row * cols + neighbour_col;
+                    // Every neighbouring pair meets once, from its lower-indexed cell.
+                    if neighbour < cell {
+                        continue;
+                    }
+
+                    let (left, right) = self.play_pair(
+                        *self.map.get(row, col),
+                        *self.map.get(neighbour_row, neighbour_col),
+                        (cell * rows * cols + neighbour) as u64,
+                    );
+                    scores.set(scores.get(row, col) + left, row, col);
+                    scores.set(
+                        scores.get(neighbour_row, neighbour_col) + right,
+                        neighbour_row,
+                        neighbour_col,
+                    );
+                }
+            }
+        }
+
+        scores
+    }
+
+    fn play_pair(&self, left: usize, right: usize, stream: u64) -> (i32, i32) {
+        let mut game = Game::with_payoffs(
+            (self.species[left].1)(),
+            (self.species[right].1)(),
+            self.payoffs,
+        );
+        game.set_total_rounds(self.rounds);
+        if let Some(noise) = &self.noise {
+            let cells = self.map.size().0 * self.map.size().1;
+            game.set_noise(noise.fork(stream + (self.step * cells * cells) as u64));
+        }
+
+        for _ in 0..self.rounds {
+            game.play_round();
+        }
+        (game.left_score(), game.right_score())
+    }
+}
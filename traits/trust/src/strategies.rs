@@ -0,0 +1,366 @@
+use crate::{rng::Rng, Agent, Decision, GameFamily, MatchView, PayoffMatrix};
+
+use thiserror::Error;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct TitForTwoTatsAgent {
+    my_next_decision: Decision,
+}
+
+impl TitForTwoTatsAgent {
+    pub fn new() -> Self {
+        Self {
+            my_next_decision: Decision::Cooperated,
+        }
+    }
+}
+
+impl Agent for TitForTwoTatsAgent {
+    fn play(&mut self) -> Decision {
+        self.my_next_decision
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        let mut last_moves = history.enemy_moves().rev();
+
+        self.my_next_decision = match (last_moves.next(), last_moves.next()) {
+            (Some(Decision::Cheeted), Some(Decision::Cheeted)) => Decision::Cheeted,
+            _ => Decision::Cooperated,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct GenerousTitForTatAgent {
+    generosity: f64,
+    rng: Rng,
+    my_next_decision: Decision,
+}
+
+impl GenerousTitForTatAgent {
+    pub fn new(generosity: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&generosity),
+            "generosity must be within [0, 1]"
+        );
+
+        Self {
+            generosity,
+            rng: Rng::new(seed),
+            my_next_decision: Decision::Cooperated,
+        }
+    }
+}
+
+impl Agent for GenerousTitForTatAgent {
+    fn play(&mut self) -> Decision {
+        self.my_next_decision
+    }
+
+    fn accept_enemy_decision(&mut self, decision: Decision) {
+        self.my_next_decision = match decision {
+            Decision::Cheeted if !self.rng.gen_bool(self.generosity) => Decision::Cheeted,
+            _ => Decision::Cooperated,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct PavlovAgent {
+    my_next_decision: Decision,
+}
+
+impl PavlovAgent {
+    pub fn new() -> Self {
+        Self {
+            my_next_decision: Decision::Cooperated,
+        }
+    }
+}
+
+impl Agent for PavlovAgent {
+    fn play(&mut self) -> Decision {
+        self.my_next_decision
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        // Win-stay, lose-shift: keep the move if the enemy cooperated, switch otherwise.
+        self.my_next_decision = match (history.my_last_move(), history.enemy_last_move()) {
+            (Some(mine), Some(Decision::Cooperated)) => mine,
+            (Some(mine), Some(Decision::Cheeted)) => mine.flipped(),
+            _ => Decision::Cooperated,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct RandomAgent {
+    cooperation_probability: f64,
+    rng: Rng,
+}
+
+impl RandomAgent {
+    pub fn new(cooperation_probability: f64, seed: u64) -> Self {
+        assert!(
+            (0.0..=1.0).contains(&cooperation_probability),
+            "cooperation probability must be within [0, 1]"
+        );
+
+        Self {
+            cooperation_probability,
+            rng: Rng::new(seed),
+        }
+    }
+}
+
+impl Agent for RandomAgent {
+    fn play(&mut self) -> Decision {
+        if self.rng.gen_bool(self.cooperation_probability) {
+            Decision::Cooperated
+        } else {
+            Decision::Cheeted
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct AlternatorAgent {
+    my_next_decision: Decision,
+}
+
+impl AlternatorAgent {
+    pub fn new() -> Self {
+        Self {
+            my_next_decision: Decision::Cooperated,
+        }
+    }
+}
+
+impl Agent for AlternatorAgent {
+    fn play(&mut self) -> Decision {
+        let decision = self.my_next_decision;
+        self.my_next_decision = decision.flipped();
+        decision
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct SoftMajorityAgent {
+    enemy_cooperations: u32,
+    enemy_cheats: u32,
+}
+
+impl SoftMajorityAgent {
+    pub fn new() -> Self {
+        Self {
+            enemy_cooperations: 0,
+            enemy_cheats: 0,
+        }
+    }
+}
+
+impl Agent for SoftMajorityAgent {
+    fn play(&mut self) -> Decision {
+        if self.enemy_cooperations >= self.enemy_cheats {
+            Decision::Cooperated
+        } else {
+            Decision::Cheeted
+        }
+    }
+
+    fn accept_enemy_decision(&mut self, decision: Decision) {
+        match decision {
+            Decision::Cooperated => self.enemy_cooperations += 1,
+            Decision::Cheeted => self.enemy_cheats += 1,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Default)]
+pub struct HardMajorityAgent {
+    enemy_cooperations: u32,
+    enemy_cheats: u32,
+}
+
+impl HardMajorityAgent {
+    pub fn new() -> Self {
+        Self {
+            enemy_cooperations: 0,
+            enemy_cheats: 0,
+        }
+    }
+}
+
+impl Agent for HardMajorityAgent {
+    fn play(&mut self) -> Decision {
+        if self.enemy_cheats >= self.enemy_cooperations {
+            Decision::Cheeted
+        } else {
+            Decision::Cooperated
+        }
+    }
+
+    fn accept_enemy_decision(&mut self, decision: Decision) {
+        match decision {
+            Decision::Cooperated => self.enemy_cooperations += 1,
+            Decision::Cheeted => self.enemy_cheats += 1,
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub struct ProberAgent {
+    my_next_decision: Decision,
+}
+
+impl Default for ProberAgent {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ProberAgent {
+    pub fn new() -> Self {
+        Self {
+            my_next_decision: Decision::Cheeted,
+        }
+    }
+}
+
+impl Agent for ProberAgent {
+    fn play(&mut self) -> Decision {
+        self.my_next_decision
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        // Opens with cheat, cooperate, cooperate and exploits enemies that never retaliated.
+        self.my_next_decision = match history.round() {
+            0 => Decision::Cheeted,
+            1 | 2 => Decision::Cooperated,
+            _ => {
+                let mut enemy_moves = history.enemy_moves().skip(1);
+                let is_naive = enemy_moves.next() == Some(Decision::Cooperated)
+                    && enemy_moves.next() == Some(Decision::Cooperated);
+
+                if is_naive {
+                    Decision::Cheeted
+                } else {
+                    history.enemy_last_move().unwrap_or_default()
+                }
+            }
+        }
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Error, Clone, Copy, Debug, PartialEq)]
+pub enum ExtortionError {
+    #[error("extortion factor must be at least 1, got {0}")]
+    Factor(f64),
+    #[error("no zero-determinant strategy enforces factor {factor} in this {family} game")]
+    Infeasible { family: GameFamily, factor: f64 },
+}
+
+pub struct ExtortionAgent {
+    cooperation_probabilities: [f64; 4],
+    rng: Rng,
+    my_next_decision: Decision,
+}
+
+impl ExtortionAgent {
+    pub fn new(payoffs: PayoffMatrix, factor: f64, seed: u64) -> Result<Self, ExtortionError> {
+        Ok(Self::with_probabilities(
+            Self::probabilities(payoffs, factor)?,
+            seed,
+        ))
+    }
+
+    // Press and Dyson's zero-determinant strategies: after CC, CD, DC and DD the
+    // agent cooperates with p = (1, 1, 0, 0) + phi * ((R, S, T, P) - P - factor * ((R, T, S, P) - P)),
+    // which pins its surplus over P to `factor` times the enemy's. Taking half of
+    // the largest feasible phi gives Extort-2 for (R, S, T, P) = (3, 0, 5, 1).
+    pub fn probabilities(payoffs: PayoffMatrix, factor: f64) -> Result<[f64; 4], ExtortionError> {
+        if !(factor >= 1.0 && factor.is_finite()) {
+            return Err(ExtortionError::Factor(factor));
+        }
+
+        let reward = f64::from(payoffs.reward() - payoffs.punishment());
+        let sucker = f64::from(payoffs.punishment() - payoffs.sucker());
+        let temptation = f64::from(payoffs.temptation() - payoffs.punishment());
+        let slopes = [
+            (factor - 1.0) * reward,
+            sucker + factor * temptation,
+            temptation + factor * sucker,
+        ];
+
+        let steepest = slopes.iter().copied().fold(0.0, f64::max);
+        if slopes.iter().any(|&slope| slope < 0.0) || steepest == 0.0 {
+            return Err(ExtortionError::Infeasible {
+                family: payoffs.family(),
+                factor,
+            });
+        }
+
+        let phi = 0.5 / steepest;
+        Ok([
+            1.0 - phi * slopes[0],
+            1.0 - phi * slopes[1],
+            phi * slopes[2],
+            0.0,
+        ])
+    }
+
+    pub fn with_probabilities(cooperation_probabilities: [f64; 4], seed: u64) -> Self {
+        assert!(
+            cooperation_probabilities
+                .iter()
+                .all(|probability| (0.0..=1.0).contains(probability)),
+            "cooperation probabilities must be within [0, 1]"
+        );
+
+        Self {
+            cooperation_probabilities,
+            rng: Rng::new(seed),
+            my_next_decision: Decision::Cooperated,
+        }
+    }
+}
+
+impl Agent for ExtortionAgent {
+    fn play(&mut self) -> Decision {
+        self.my_next_decision
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        let state = match (history.my_last_move(), history.enemy_last_move()) {
+            (Some(Decision::Cooperated), Some(Decision::Cooperated)) => 0,
+            (Some(Decision::Cooperated), Some(Decision::Cheeted)) => 1,
+            (Some(Decision::Cheeted), Some(Decision::Cooperated)) => 2,
+            (Some(Decision::Cheeted), Some(Decision::Cheeted)) => 3,
+            _ => {
+                self.my_next_decision = Decision::Cooperated;
+                return;
+            }
+        };
+
+        self.my_next_decision = if self.rng.gen_bool(self.cooperation_probabilities[state]) {
+            Decision::Cooperated
+        } else {
+            Decision::Cheeted
+        }
+    }
+}
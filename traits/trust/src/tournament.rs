@@ -0,0 +1,288 @@
+use crate::{Agent, Continuation, Game, Noise, PayoffMatrix, RoundOutcome};
+
+use std::cmp::{Ordering, Reverse};
+
+////////////////////////////////////////////////////////////////////////////////
+
+pub type AgentFactory = Box<dyn Fn() -> Box<dyn Agent>>;
+
+pub struct Tournament {
+    roster: Vec<(String, AgentFactory)>,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    continuation: Option<Continuation>,
+}
+
+impl Tournament {
+    pub fn new(rounds: u32) -> Self {
+        Self {
+            roster: vec![],
+            rounds,
+            payoffs: PayoffMatrix::default(),
+            noise: None,
+            continuation: None,
+        }
+    }
+
+    pub fn set_payoffs(&mut self, payoffs: PayoffMatrix) -> &mut Self {
+        self.payoffs = payoffs;
+        self
+    }
+
+    pub fn set_noise(&mut self, noise: Noise) -> &mut Self {
+        self.noise = Some(noise);
+        self
+    }
+
+    // Overrides the fixed number of rounds with a stochastic end of every match.
+    pub fn set_continuation(&mut self, continuation: Continuation) -> &mut Self {
+        self.continuation = Some(continuation);
+        self
+    }
+
+    pub fn add_agent<S, F>(&mut self, name: S, factory: F) -> &mut Self
+    where
+        S: Into<String>,
+        F: Fn() -> Box<dyn Agent> + 'static,
+    {
+        self.roster.push((name.into(), Box::new(factory)));
+        self
+    }
+
+    pub fn rounds(&self) -> u32 {
+        self.rounds
+    }
+
+    pub fn len(&self) -> usize {
+        self.roster.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.roster.is_empty()
+    }
+
+    pub fn play(&self) -> TournamentResult {
+        let mut result = TournamentResult::new(self.roster.iter().map(|(name, _)| name.clone()));
+
+        for left in 0..self.roster.len() {
+            for right in left + 1..self.roster.len() {
+                let pairing = self.play_pairing(left, right);
+                result.record(left, right, pairing);
+            }
+        }
+
+        result
+    }
+
+    fn play_pairing(&self, left: usize, right: usize) -> Pairing {
+        let stream = pairing_stream(self.roster.len(), left, right);
+
+        play_pairing(
+            (self.roster[left].1)(),
+            (self.roster[right].1)(),
+            self.rounds,
+            self.payoffs,
+            self.noise.as_ref().map(|noise| noise.fork(stream)),
+            self.continuation
+                .as_ref()
+                .map(|continuation| continuation.fork(stream)),
+        )
+    }
+}
+
+pub(crate) fn pairing_stream(players: usize, left: usize, right: usize) -> u64 {
+    (left * players + right) as u64
+}
+
+pub(crate) fn play_pairing(
+    left: Box<dyn Agent>,
+    right: Box<dyn Agent>,
+    rounds: u32,
+    payoffs: PayoffMatrix,
+    noise: Option<Noise>,
+    continuation: Option<Continuation>,
+) -> Pairing {
+    let mut game = Game::with_payoffs(left, right, payoffs);
+    if let Some(noise) = noise {
+        game.set_noise(noise);
+    }
+
+    match continuation {
+        Some(mut continuation) => {
+            game.play_with_continuation(&mut continuation);
+        }
+        None => {
+            game.set_total_rounds(rounds);
+            for _ in 0..rounds {
+                game.play_round();
+            }
+        }
+    }
+
+    let mut pairing = Pairing {
+        left_score: game.left_score(),
+        right_score: game.right_score(),
+        rounds: game.history().len() as u32,
+        ..Pairing::default()
+    };
+    for round in game.history() {
+        pairing.outcomes.add(round.outcome);
+    }
+
+    pairing
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct OutcomeCounts {
+    pub both_cooperated: u32,
+    pub left_cheated: u32,
+    pub right_cheated: u32,
+    pub both_cheated: u32,
+}
+
+impl OutcomeCounts {
+    pub fn add(&mut self, outcome: RoundOutcome) {
+        match outcome {
+            RoundOutcome::BothCooperated => self.both_cooperated += 1,
+            RoundOutcome::LeftCheated => self.left_cheated += 1,
+            RoundOutcome::RightCheated => self.right_cheated += 1,
+            RoundOutcome::BothCheated => self.both_cheated += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.both_cooperated + self.left_cheated + self.right_cheated + self.both_cheated
+    }
+
+    pub fn swapped(&self) -> Self {
+        Self {
+            both_cooperated: self.both_cooperated,
+            left_cheated: self.right_cheated,
+            right_cheated: self.left_cheated,
+            both_cheated: self.both_cheated,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Pairing {
+    pub left_score: i32,
+    pub right_score: i32,
+    pub rounds: u32,
+    pub outcomes: OutcomeCounts,
+}
+
+impl Pairing {
+    pub fn swapped(&self) -> Self {
+        Self {
+            left_score: self.right_score,
+            right_score: self.left_score,
+            rounds: self.rounds,
+            outcomes: self.outcomes.swapped(),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Standing {
+    pub name: String,
+    pub score: i32,
+    pub wins: u32,
+    pub draws: u32,
+    pub losses: u32,
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TournamentResult {
+    names: Vec<String>,
+    pairings: Vec<Vec<Option<Pairing>>>,
+}
+
+impl TournamentResult {
+    pub(crate) fn new(names: impl Iterator<Item = String>) -> Self {
+        let names = names.collect::<Vec<_>>();
+        let pairings = vec![vec![None; names.len()]; names.len()];
+        Self { names, pairings }
+    }
+
+    pub(crate) fn record(&mut self, left: usize, right: usize, pairing: Pairing) {
+        self.pairings[left][right] = Some(pairing);
+        self.pairings[right][left] = Some(pairing.swapped());
+    }
+
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn pairing(&self, left: usize, right: usize) -> Option<&Pairing> {
+        self.pairings[left][right].as_ref()
+    }
+
+    pub fn score_matrix(&self) -> Vec<Vec<i32>> {
+        self.pairings
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|pairing| pairing.map_or(0, |pairing| pairing.left_score))
+                    .collect()
+            })
+            .collect()
+    }
+
+    pub fn average_rounds(&self) -> f64 {
+        let rounds = self
+            .pairings
+            .iter()
+            .enumerate()
+            .flat_map(|(left, row)| row.iter().skip(left + 1).flatten())
+            .map(|pairing| pairing.rounds)
+            .collect::<Vec<_>>();
+
+        if rounds.is_empty() {
+            0.0
+        } else {
+            rounds.iter().sum::<u32>() as f64 / rounds.len() as f64
+        }
+    }
+
+    pub fn total_score(&self, index: usize) -> i32 {
+        self.pairings[index]
+            .iter()
+            .flatten()
+            .map(|pairing| pairing.left_score)
+            .sum()
+    }
+
+    pub fn leaderboard(&self) -> Vec<Standing> {
+        let mut standings = self
+            .names
+            .iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let mut standing = Standing {
+                    name: name.clone(),
+                    score: self.total_score(index),
+                    wins: 0,
+                    draws: 0,
+                    losses: 0,
+                };
+                for pairing in self.pairings[index].iter().flatten() {
+                    match pairing.left_score.cmp(&pairing.right_score) {
+                        Ordering::Greater => standing.wins += 1,
+                        Ordering::Equal => standing.draws += 1,
+                        Ordering::Less => standing.losses += 1,
+                    }
+                }
+                standing
+            })
+            .collect::<Vec<_>>();
+
+        standings.sort_by_key(|standing| Reverse(standing.score));
+        standings
+    }
+}
@@ -0,0 +1,48 @@
+use trust::{Estimate, Game, Noise, Report, StrategyRegistry};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn csv_ends_with_the_summary() {
+    let registry = StrategyRegistry::new();
+    let histories = (0..4)
+        .map(|seed| {
+            let mut game = Game::new(
+                registry.create("copycat", seed).unwrap(),
+                registry.create("random", seed).unwrap(),
+            );
+            game.set_noise(Noise::new(0.1, seed));
+            for _ in 0..20 {
+                game.play_round();
+            }
+            game.history().clone()
+        })
+        .collect::<Vec<_>>();
+    let report = Report::new(&histories);
+
+    let csv = report.to_csv();
+    let (matches, summary) = csv.split_once("\n\n").unwrap();
+    assert_eq!(matches.lines().count(), 1 + histories.len());
+
+    let mut rows = summary.lines();
+    assert_eq!(rows.next(), Some("metric,mean,std_dev,lower,upper"));
+    for (row, (name, estimate)) in rows.zip(report.summary()) {
+        let fields = row.split(',').collect::<Vec<_>>();
+        assert_eq!(fields[0], name);
+        let values = fields[1..]
+            .iter()
+            .map(|field| field.parse::<f64>().unwrap())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            Estimate {
+                mean: values[0],
+                std_dev: values[1],
+                lower: values[2],
+                upper: values[3],
+            },
+            estimate
+        );
+        assert!(estimate.lower <= estimate.mean && estimate.mean <= estimate.upper);
+    }
+    assert_eq!(summary.lines().count(), 1 + report.summary().len());
+}
@@ -0,0 +1,102 @@
+use trust::{Decision, Experiment, ExperimentError, History, Mode};
+
+use std::path::Path;
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn parse(source: &str) -> Experiment {
+    source.parse().unwrap()
+}
+
+fn run(experiment: &Experiment) -> String {
+    let registry = experiment.registry(Path::new(".")).unwrap();
+    experiment.run(&registry).unwrap().data
+}
+
+#[test]
+fn round_trips_experiments() {
+    let sources = [
+        "mode = match\nagents = copycat, random(0.3)\nrounds = 10\n",
+        "mode = tournament\nagents = copycat, grudger, extortion(3)\nrounds = 50\n\
+         payoffs = prisoners-dilemma 5 3 1 0\nnoise = 0.05\ncontinuation = 0.9\nseed = 42\n\
+         output = results/tournament.csv\n",
+        "mode = evolution\nagents = copycat, cheating, mine\ncounts = 10, 5, 5\nrounds = 20\n\
+         payoffs = snowdrift 4 2\ngenerations = 30\nreplaced = 3\nscript.mine = mine.fsm\n",
+    ];
+
+    for source in sources {
+        let experiment = parse(source);
+        let written = experiment.to_string();
+        assert_eq!(
+            written.parse::<Experiment>().unwrap(),
+            experiment,
+            "{written}"
+        );
+    }
+}
+
+#[test]
+fn writes_only_the_keys_that_are_set() {
+    let experiment =
+        parse("# a short match\nmode = match\nagents = copycat, cheating\nrounds = 5\n");
+    assert_eq!(experiment.mode, Mode::Match);
+    assert_eq!(
+        experiment.to_string(),
+        "mode = match\nagents = copycat, cheating\nrounds = 5\n\
+         payoffs = prisoners-dilemma 3 2 0 -1\nseed = 0\n"
+    );
+}
+
+#[test]
+fn rejects_unknown_keys() {
+    let error = "mode = match\nagents = copycat, cheating\nrounds = 5\nnosie = 0.1\n"
+        .parse::<Experiment>()
+        .unwrap_err();
+    assert!(matches!(
+        error,
+        ExperimentError::UnknownKey { line: 4, ref key } if key == "nosie"
+    ));
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// The noise and every agent draw from their own stream. A random agent whose
+// coin is the noise's coin would see each of its cooperations flipped into a
+// cheat and always be observed cheating.
+#[test]
+fn noise_and_agents_use_separate_streams() {
+    let runs = 200;
+    let cheats = (0..runs)
+        .filter(|seed| {
+            let experiment = parse(&format!(
+                "mode = match\nagents = random(0.5), cooperating\nrounds = 1\n\
+                 noise = 0.5\nseed = {seed}\n"
+            ));
+            let history = run(&experiment).parse::<History>().unwrap();
+            history.rounds()[0].left.observed == Decision::Cheeted
+        })
+        .count();
+
+    assert!((70..=130).contains(&cheats), "{cheats} of {runs}");
+}
+
+#[test]
+fn output_is_resolved_like_scripts() {
+    let experiment =
+        parse("mode = match\nagents = copycat, cheating\nrounds = 5\noutput = out.txt\n");
+    assert_eq!(
+        experiment.output_path(Path::new("experiments")),
+        Some(Path::new("experiments").join("out.txt"))
+    );
+}
+
+#[test]
+fn rejects_scores_that_overflow() {
+    let source = "mode = match\nagents = cooperating, cooperating\nrounds = 3\n\
+                  payoffs = prisoners-dilemma 1500000000 1000000000 1 0\n";
+    let error = source.parse::<Experiment>().unwrap_err();
+    assert!(matches!(error, ExperimentError::Invalid(_)), "{error}");
+
+    // A single round still fits.
+    run(&parse(&source.replace("rounds = 3", "rounds = 1")));
+}
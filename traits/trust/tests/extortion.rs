@@ -0,0 +1,58 @@
+use trust::{
+    ExtortionAgent, ExtortionError, Game, GameFamily, PayoffMatrix, RandomAgent, StrategyError,
+    StrategyRegistry,
+};
+
+#[test]
+fn reproduces_press_dyson_extort_2() {
+    let payoffs = PayoffMatrix::new(GameFamily::PrisonersDilemma, 5, 3, 1, 0).unwrap();
+    let probabilities = ExtortionAgent::probabilities(payoffs, 2.0).unwrap();
+
+    let expected = [8.0 / 9.0, 0.5, 1.0 / 3.0, 0.0];
+    for (actual, expected) in probabilities.iter().zip(expected) {
+        assert!((actual - expected).abs() < 1e-12, "{probabilities:?}");
+    }
+}
+
+#[test]
+fn enforces_factor_with_default_payoffs() {
+    let payoffs = PayoffMatrix::default();
+    for factor in [1.5, 2.0, 3.0] {
+        let mut game = Game::new(
+            Box::new(ExtortionAgent::new(payoffs, factor, 1).unwrap()),
+            Box::new(RandomAgent::new(0.7, 2)),
+        );
+        for _ in 0..200_000 {
+            game.play_round();
+        }
+
+        // The default matrix has P = 0, so the surplus is the score itself.
+        let ratio = f64::from(game.left_score()) / f64::from(game.right_score());
+        assert!((ratio - factor).abs() < 0.05 * factor, "{factor}: {ratio}");
+    }
+}
+
+#[test]
+fn rejects_infeasible_games() {
+    assert_eq!(
+        ExtortionAgent::probabilities(PayoffMatrix::default(), 0.5),
+        Err(ExtortionError::Factor(0.5))
+    );
+
+    let chicken = PayoffMatrix::new(GameFamily::Chicken, 3, 2, 0, 1).unwrap();
+    assert!(ExtortionAgent::probabilities(chicken, 2.0).is_ok());
+    assert_eq!(
+        ExtortionAgent::probabilities(chicken, 4.0),
+        Err(ExtortionError::Infeasible {
+            family: GameFamily::Chicken,
+            factor: 4.0
+        })
+    );
+
+    let registry = StrategyRegistry::with_payoffs(chicken);
+    assert!(registry.create("extortion(2)", 0).is_ok());
+    assert!(matches!(
+        registry.create("extortion(4)", 0),
+        Err(StrategyError::Extortion(ExtortionError::Infeasible { .. }))
+    ));
+}
@@ -0,0 +1,164 @@
+use trust::{
+    Agent, CooperatingAgent, Decision, Game, History, HistoryError, MatchView, Noise, ReplayError,
+    StrategyRegistry,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn played(left: &str, right: &str, seed: u64) -> History {
+    let registry = StrategyRegistry::new();
+    let mut game = Game::new(
+        registry.create(left, seed).unwrap(),
+        registry.create(right, seed + 1).unwrap(),
+    );
+    game.set_noise(Noise::new(0.2, seed));
+    for _ in 0..40 {
+        game.play_round();
+    }
+    game.history().clone()
+}
+
+#[test]
+fn round_trips_played_matches() {
+    for (left, right) in [
+        ("copycat", "random"),
+        ("detective", "pavlov"),
+        ("grudger", "prober"),
+    ] {
+        for seed in 0..5 {
+            let history = played(left, right, seed);
+            let text = history.to_string();
+            assert!(text.starts_with("trust-history v1\n"));
+            assert_eq!(text.parse::<History>().unwrap(), history, "{text}");
+        }
+    }
+}
+
+#[test]
+fn round_trips_empty_history() {
+    let history = History::new();
+    assert_eq!(history.to_string(), "trust-history v1\n");
+    assert_eq!("trust-history v1".parse::<History>().unwrap(), history);
+}
+
+#[test]
+fn records_mistakes() {
+    let history = "trust-history v1\nCD CC -1 3\n".parse::<History>().unwrap();
+    let round = history.last().unwrap();
+    assert!(round.left.is_mistake());
+    assert!(!round.right.is_mistake());
+    assert_eq!((round.left_score, round.right_score), (-1, 3));
+}
+
+#[test]
+fn reports_malformed_input() {
+    let cases = [
+        ("", HistoryError::MissingHeader),
+        ("CC CC 2 2\n", HistoryError::MissingHeader),
+        (
+            "trust-history v1\nCC CC 2\n",
+            HistoryError::FieldCount { line: 2, found: 3 },
+        ),
+        (
+            "trust-history v1\n\nCC CX 2 2\n",
+            HistoryError::InvalidMove {
+                line: 3,
+                value: "CX".to_string(),
+            },
+        ),
+        (
+            "trust-history v1\nCC CCC 2 2\n",
+            HistoryError::InvalidMove {
+                line: 2,
+                value: "CCC".to_string(),
+            },
+        ),
+        (
+            "trust-history v1\nCC CC two 2\n",
+            HistoryError::InvalidScore {
+                line: 2,
+                value: "two".to_string(),
+            },
+        ),
+    ];
+
+    for (source, error) in cases {
+        assert_eq!(source.parse::<History>(), Err(error), "{source:?}");
+    }
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+// Cooperates until the announced last round, then cheats.
+struct LastRoundCheater {
+    is_last_round: bool,
+}
+
+impl Agent for LastRoundCheater {
+    fn play(&mut self) -> Decision {
+        if self.is_last_round {
+            Decision::Cheeted
+        } else {
+            Decision::Cooperated
+        }
+    }
+
+    fn accept_history(&mut self, history: &MatchView<'_>) {
+        self.is_last_round = history.remaining_rounds() == Some(1);
+    }
+}
+
+fn horizon_game() -> Game {
+    Game::new(
+        Box::new(LastRoundCheater {
+            is_last_round: false,
+        }),
+        Box::new(CooperatingAgent::default()),
+    )
+}
+
+#[test]
+fn round_trips_total_rounds() {
+    let mut game = horizon_game();
+    game.set_total_rounds(3);
+    for _ in 0..3 {
+        game.play_round();
+    }
+
+    let text = game.history().to_string();
+    assert!(
+        text.starts_with("trust-history v1 total-rounds=3\n"),
+        "{text}"
+    );
+    let history = text.parse::<History>().unwrap();
+    assert_eq!(history.total_rounds(), Some(3));
+    assert_eq!(&history, game.history());
+
+    // The recorded horizon makes the agent cheat in the last round again.
+    let mut replayed = horizon_game();
+    replayed.replay(&history).unwrap();
+    assert_eq!(replayed.history(), game.history());
+}
+
+#[test]
+fn replay_needs_a_fresh_game() {
+    let mut game = horizon_game();
+    game.play_round();
+    let history = game.history().clone();
+
+    assert_eq!(game.replay(&history), Err(ReplayError::AlreadyPlayed(1)));
+}
+
+#[test]
+fn reports_invalid_total_rounds() {
+    assert_eq!(
+        "trust-history v1 total-rounds=many\n".parse::<History>(),
+        Err(HistoryError::InvalidTotalRounds(
+            "total-rounds=many".to_string()
+        ))
+    );
+    assert_eq!(
+        "trust-history v10\n".parse::<History>(),
+        Err(HistoryError::MissingHeader)
+    );
+}
@@ -0,0 +1,80 @@
+use trust::{
+    AgentFactory, CooperatingAgent, Decision, Game, GeneticConfig, GeneticTrainer, LookupTable,
+    LookupTableError, Noise, StrategyRegistry,
+};
+
+use std::sync::Arc;
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn round_trips_tables() {
+    let table = "memory-1 CCDCD".parse::<LookupTable>().unwrap();
+    assert_eq!(table.memory(), 1);
+    assert_eq!(table.decisions()[2], Decision::Cheeted);
+    assert_eq!(table.to_string(), "memory-1 CCDCD");
+}
+
+#[test]
+fn round_trips_evolved_tables() {
+    let registry = StrategyRegistry::new();
+    let opponents = ["copycat", "cheating", "random"]
+        .into_iter()
+        .map(|name| Box::new(registry.factory(name, 3).unwrap()) as AgentFactory)
+        .collect::<Vec<_>>();
+    let mut trainer = GeneticTrainer::new(
+        GeneticConfig {
+            memory: 2,
+            population: 6,
+            elite: 2,
+            rounds: 16,
+            ..GeneticConfig::default()
+        },
+        7,
+    );
+
+    let table = trainer.evolve(&opponents, 2);
+    assert_eq!(table.to_string().parse::<LookupTable>().unwrap(), table);
+}
+
+#[test]
+fn reports_malformed_tables() {
+    let cases = [
+        ("CCDCD", LookupTableError::Syntax),
+        ("memory-x CCDCD", LookupTableError::Syntax),
+        ("memory-0 C", LookupTableError::Memory(0)),
+        ("memory-9 C", LookupTableError::Memory(9)),
+        (
+            "memory-1 CCDC",
+            LookupTableError::Length {
+                expected: 5,
+                found: 4,
+            },
+        ),
+        ("memory-1 CCXCD", LookupTableError::Decision('X')),
+    ];
+
+    for (source, error) in cases {
+        assert_eq!(source.parse::<LookupTable>(), Err(error), "{source:?}");
+    }
+}
+
+#[test]
+fn lookup_agents_see_observed_moves() {
+    // Repeats its own last move as observed, so every flip by noise sticks.
+    let table = Arc::new("memory-1 CCCDD".parse::<LookupTable>().unwrap());
+    let mut game = Game::new(
+        Box::new(table.agent()),
+        Box::new(CooperatingAgent::default()),
+    );
+    game.set_noise(Noise::new(0.2, 5));
+    for _ in 0..100 {
+        game.play_round();
+    }
+
+    let rounds = game.history().rounds();
+    assert!(rounds.iter().any(|round| round.left.is_mistake()));
+    for pair in rounds.windows(2) {
+        assert_eq!(pair[1].left.intended, pair[0].left.observed);
+    }
+}
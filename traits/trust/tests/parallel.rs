@@ -0,0 +1,50 @@
+use trust::{Continuation, Noise, ParallelTournament, StrategyRegistry, Tournament};
+
+////////////////////////////////////////////////////////////////////////////////
+
+const AGENTS: [&str; 6] = [
+    "copycat",
+    "grudger",
+    "random(0.4)",
+    "detective",
+    "pavlov",
+    "cheating",
+];
+
+fn tournaments(rounds: u32, threads: usize) -> (Tournament, ParallelTournament) {
+    let registry = StrategyRegistry::new();
+    let mut sequential = Tournament::new(rounds);
+    let mut parallel = ParallelTournament::new(rounds);
+    parallel.set_threads(threads);
+
+    for (seed, agent) in AGENTS.into_iter().enumerate() {
+        let seed = seed as u64;
+        sequential.add_agent(agent, registry.factory(agent, seed).unwrap());
+        parallel.add_agent(agent, registry.send_factory(agent, seed).unwrap());
+    }
+    (sequential, parallel)
+}
+
+#[test]
+fn matches_the_sequential_tournament() {
+    for threads in [1, 3, 8] {
+        let (mut sequential, mut parallel) = tournaments(50, threads);
+        sequential.set_noise(Noise::new(0.1, 17));
+        parallel.set_noise(Noise::new(0.1, 17));
+
+        assert_eq!(parallel.play(), sequential.play(), "{threads} threads");
+    }
+}
+
+#[test]
+fn matches_the_sequential_tournament_with_continuation() {
+    let (mut sequential, mut parallel) = tournaments(100, 4);
+    let noise = Noise::new(0.05, 3);
+    let continuation = Continuation::new(0.9, 8);
+    sequential
+        .set_noise(noise.clone())
+        .set_continuation(continuation.clone());
+    parallel.set_noise(noise).set_continuation(continuation);
+
+    assert_eq!(parallel.play(), sequential.play());
+}
@@ -0,0 +1,21 @@
+use trust::{GameFamily, PayoffError, PayoffMatrix};
+
+////////////////////////////////////////////////////////////////////////////////
+
+#[test]
+fn large_payoffs_do_not_overflow() {
+    let new = |t, r, p, s| PayoffMatrix::new(GameFamily::PrisonersDilemma, t, r, p, s);
+    assert!(new(2_000_000_001, 2_000_000_000, 1, 0).is_ok());
+    assert!(new(i32::MAX, i32::MAX - 1, 1, 0).is_ok());
+    assert_eq!(
+        new(2_000_000_000, 1_000_000_000, 1, 0),
+        Err(PayoffError::AlternationPays)
+    );
+
+    assert_eq!(
+        PayoffMatrix::snowdrift(1_500_000_000, 1),
+        Err(PayoffError::Overflow)
+    );
+    let matrix = PayoffMatrix::snowdrift(1_000_000_000, 2).unwrap();
+    assert_eq!(matrix.sucker(), 1_999_999_996);
+}
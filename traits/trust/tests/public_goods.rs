@@ -0,0 +1,62 @@
+use trust::{
+    Agent, CheatingAgent, CooperatingAgent, CopycatAgent, Decision, GroupAdapter, GroupAgent,
+    ProberAgent, PublicGoodsGame, TitForTwoTatsAgent,
+};
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn group(
+    first: Box<dyn Agent>,
+    others: usize,
+    other: impl Fn() -> Box<dyn Agent>,
+) -> PublicGoodsGame {
+    let mut players: Vec<Box<dyn GroupAgent>> = vec![Box::new(GroupAdapter::new(first))];
+    for _ in 0..others {
+        players.push(Box::new(GroupAdapter::new(other())));
+    }
+    PublicGoodsGame::new(players, 2.0)
+}
+
+fn first_player_moves(game: &mut PublicGoodsGame, rounds: usize) -> Vec<Decision> {
+    (0..rounds)
+        .map(|_| game.play_round().decisions[0])
+        .collect()
+}
+
+#[test]
+fn prober_probes_the_group() {
+    use Decision::{Cheeted as D, Cooperated as C};
+
+    let mut game = group(Box::new(ProberAgent::new()), 3, || {
+        Box::new(CooperatingAgent::default())
+    });
+    assert_eq!(first_player_moves(&mut game, 5), [D, C, C, D, D]);
+
+    // A copycat retaliates against the opening cheat, so the prober plays tit for tat.
+    let mut game = group(Box::new(ProberAgent::new()), 1, || {
+        Box::new(CopycatAgent::new())
+    });
+    assert_eq!(first_player_moves(&mut game, 5), [D, C, C, C, C]);
+}
+
+#[test]
+fn tit_for_two_tats_answers_a_cheating_group() {
+    use Decision::{Cheeted as D, Cooperated as C};
+
+    let mut game = group(Box::new(TitForTwoTatsAgent::new()), 3, || {
+        Box::new(CheatingAgent::default())
+    });
+    assert_eq!(first_player_moves(&mut game, 4), [C, C, D, D]);
+}
+
+#[test]
+fn adapter_records_the_group_as_one_enemy() {
+    let mut adapter = GroupAdapter::new(Box::new(CopycatAgent::new()));
+    assert_eq!(adapter.play(), Decision::Cooperated);
+    adapter.accept_group_decisions(&[Decision::Cheeted, Decision::Cheeted, Decision::Cooperated]);
+
+    let round = adapter.history().last().unwrap();
+    assert_eq!(round.left.observed, Decision::Cooperated);
+    assert_eq!(round.right.observed, Decision::Cheeted);
+    assert_eq!(adapter.play(), Decision::Cheeted);
+}
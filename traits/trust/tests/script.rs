@@ -0,0 +1,152 @@
+use trust::{
+    Agent, Decision, Game, History, Noise, ScriptError, ScriptErrorKind, StateMachine,
+    StrategyRegistry, CHEATING_SCRIPT, COOPERATING_SCRIPT, COPYCAT_SCRIPT, DETECTIVE_SCRIPT,
+    GRUDGER_SCRIPT,
+};
+
+use std::sync::Arc;
+
+////////////////////////////////////////////////////////////////////////////////
+
+const SCRIPTS: [(&str, &str); 5] = [
+    ("cheating", CHEATING_SCRIPT),
+    ("cooperating", COOPERATING_SCRIPT),
+    ("copycat", COPYCAT_SCRIPT),
+    ("grudger", GRUDGER_SCRIPT),
+    ("detective", DETECTIVE_SCRIPT),
+];
+
+const OPPONENTS: [&str; 9] = [
+    "cheating",
+    "cooperating",
+    "copycat",
+    "grudger",
+    "detective",
+    "random",
+    "alternator",
+    "pavlov",
+    "prober",
+];
+
+fn play(left: Box<dyn Agent>, right: Box<dyn Agent>, seed: u64) -> History {
+    let mut game = Game::new(left, right);
+    game.set_noise(Noise::new(0.05, seed));
+    for _ in 0..60 {
+        game.play_round();
+    }
+    game.history().clone()
+}
+
+#[test]
+fn scripts_play_like_native_agents() {
+    let registry = StrategyRegistry::new();
+
+    for (name, source) in SCRIPTS {
+        let machine = Arc::new(StateMachine::parse(source).unwrap());
+        for opponent in OPPONENTS {
+            for seed in 0..5 {
+                let native = play(
+                    registry.create(name, 0).unwrap(),
+                    registry.create(opponent, seed).unwrap(),
+                    seed,
+                );
+                let scripted = play(
+                    Box::new(machine.agent()),
+                    registry.create(opponent, seed).unwrap(),
+                    seed,
+                );
+                assert_eq!(native, scripted, "{name} against {opponent}, seed {seed}");
+            }
+        }
+    }
+}
+
+#[test]
+fn fsm_agent_tracks_its_state() {
+    let machine = Arc::new(StateMachine::parse(GRUDGER_SCRIPT).unwrap());
+    assert_eq!(machine.start(), "trusting");
+    assert_eq!(
+        machine.states().collect::<Vec<_>>(),
+        ["trusting", "grudging"]
+    );
+
+    let mut agent = machine.agent();
+    agent.accept_enemy_decision(Decision::Cooperated);
+    assert_eq!(agent.state(), "trusting");
+    agent.accept_enemy_decision(Decision::Cheeted);
+    assert_eq!(agent.state(), "grudging");
+    agent.accept_enemy_decision(Decision::Cooperated);
+    assert_eq!(agent.state(), "grudging");
+}
+
+////////////////////////////////////////////////////////////////////////////////
+
+fn error(source: &str) -> ScriptError {
+    StateMachine::parse(source).unwrap_err()
+}
+
+#[test]
+fn reports_errors_with_line_numbers() {
+    let cases = [
+        (
+            "state a cooperate\njump a\n",
+            2,
+            ScriptErrorKind::UnknownStatement("jump".to_string()),
+        ),
+        (
+            "state a\n",
+            1,
+            ScriptErrorKind::Syntax {
+                expected: "\"state <name> <decision>\"",
+            },
+        ),
+        (
+            "# comment\n\nstate a defect\n",
+            3,
+            ScriptErrorKind::InvalidDecision("defect".to_string()),
+        ),
+        (
+            "state a cooperate\nstate a cheat\n",
+            2,
+            ScriptErrorKind::DuplicateState("a".to_string()),
+        ),
+        (
+            "state a cooperate\non a cheat -> b\n",
+            2,
+            ScriptErrorKind::UnknownState("b".to_string()),
+        ),
+        (
+            "state a cooperate\nstart a\nstart a\n",
+            3,
+            ScriptErrorKind::DuplicateStart,
+        ),
+        (
+            "state a cooperate\non a any -> a\non a cheat -> a\n",
+            3,
+            ScriptErrorKind::DuplicateTransition {
+                state: "a".to_string(),
+                decision: Decision::Cheeted,
+            },
+        ),
+        (
+            "state a cooperate\non a cheat a\n",
+            2,
+            ScriptErrorKind::Syntax {
+                expected: "\"on <state> <decision> -> <state>\"",
+            },
+        ),
+        ("# nothing here\n", 1, ScriptErrorKind::NoStates),
+    ];
+
+    for (source, line, kind) in cases {
+        assert_eq!(error(source), ScriptError { line, kind }, "{source:?}");
+    }
+}
+
+#[test]
+fn error_messages_name_the_line() {
+    assert_eq!(
+        error("state a cooperate\non a any -> a\non a cheat -> a\n").to_string(),
+        "line 3: transition from \"a\" on \"cheat\" is declared twice"
+    );
+}
@@ -0,0 +1,37 @@
+use trust::{CheatingAgent, CooperatingAgent, SpatialGame};
+
+#[test]
+fn cheater_in_a_corner_spreads_to_one_neighbour() {
+    let mut game = SpatialGame::new(2, 5, 5);
+    let cooperating = game.add_species("cooperating", || Box::new(CooperatingAgent::default()));
+    let cheating = game.add_species("cheating", || Box::new(CheatingAgent::default()));
+    for row in 0..2 {
+        for col in 0..5 {
+            game.set_cell(cooperating, row, col);
+        }
+    }
+    game.set_cell(cheating, 1, 4);
+
+    // Only the corner above sees no neighbour beating the cheater (35 against 45); the
+    // other neighbours see a cooperator with 50 and stay.
+    let steps = game.run(3);
+    for step in &steps {
+        assert_eq!(
+            step.composition,
+            vec![("cooperating".to_string(), 8), ("cheating".to_string(), 2)]
+        );
+    }
+    assert_eq!(*steps[0].map.get(0, 4), cheating);
+}
+
+#[test]
+fn composition_without_species_is_empty() {
+    let game = SpatialGame::new(2, 2, 1);
+    assert!(game.composition().is_empty());
+}
+
+#[test]
+#[should_panic(expected = "add a species before stepping the game")]
+fn step_requires_a_species() {
+    SpatialGame::new(2, 2, 1).step();
+}